@@ -0,0 +1,99 @@
+// `cargo bench --bench legal_anchors`: the bitboard lookup against checking every anchor on its own
+use std::{fs, path::Path, time::Instant};
+
+use karthograph::{
+    card::RuinIndicator,
+    card_pile::CardPile,
+    grid::{Coordinate, Cultivation, Grid},
+    shape::{Geometry, Orientation, Shape},
+};
+use rand::{prelude::SliceRandom, rngs::StdRng, Rng, SeedableRng};
+
+const BOARDS: usize = 200;
+const CULTIVATIONS: [Cultivation; 5] = [
+    Cultivation::Village,
+    Cultivation::Water,
+    Cultivation::Farm,
+    Cultivation::Forest,
+    Cultivation::Goblin,
+];
+
+fn geometries() -> Vec<Geometry> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/content.cardpile");
+    let pile: CardPile = fs::read_to_string(path).unwrap().parse().unwrap();
+    pile.cards
+        .iter()
+        .flat_map(|card| card.options(&RuinIndicator::default()))
+        .flat_map(|shape| {
+            Orientation::ALL
+                .iter()
+                .map(|orientation| orientation.apply(shape.geometry()))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// the default map with `cultivated` random single fields
+fn random_grid(rng: &mut StdRng, cultivated: usize) -> Grid {
+    let mut grid = Grid::new();
+    for _ in 0..cultivated {
+        let pos: Coordinate = (rng.gen_range(0..Grid::SIZE), rng.gen_range(0..Grid::SIZE)).into();
+        let cultivation = CULTIVATIONS.choose(rng).unwrap();
+        let field = Shape::new(&Geometry::default(), cultivation, &false.into(), false);
+        // occupied fields are skipped
+        let _ = grid.try_cultivate(&field, &pos);
+    }
+    grid
+}
+
+/// how the anchors were found before the bitboards
+fn anchors_one_by_one(grid: &Grid, geom: &Geometry, ruin: &RuinIndicator) -> usize {
+    let mut count = 0;
+    for y in 0..Grid::SIZE {
+        for x in 0..Grid::SIZE {
+            if grid.accepts_geometry_at(geom, &(x, y).into(), ruin) {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+fn measure(
+    name: &str,
+    grids: &[Grid],
+    geometries: &[Geometry],
+    anchors: impl Fn(&Grid, &Geometry, &RuinIndicator) -> usize,
+) {
+    let start = Instant::now();
+    let mut found = 0;
+    for grid in grids {
+        for geom in geometries {
+            for ruin in [false, true] {
+                found += anchors(grid, geom, &ruin.into());
+            }
+        }
+    }
+    let calls = grids.len() * geometries.len() * 2;
+    println!(
+        "{:<14} {:>8.0} ns per geometry ({} anchors)",
+        name,
+        start.elapsed().as_nanos() as f64 / calls as f64,
+        found
+    );
+}
+
+fn main() {
+    let geometries = geometries();
+    let mut rng = StdRng::seed_from_u64(32);
+    for cultivated in [20, 80] {
+        let grids: Vec<Grid> = (0..BOARDS)
+            .map(|_| random_grid(&mut rng, cultivated))
+            .collect();
+        println!("{} boards with {} random fields", BOARDS, cultivated);
+        measure("one by one", &grids, &geometries, anchors_one_by_one);
+        measure("bitboard", &grids, &geometries, |grid, geom, ruin| {
+            grid.layers().legal_anchors(geom, ruin).count()
+        });
+    }
+}
@@ -0,0 +1,49 @@
+// A bot for `cargo run --bin referee -- -- target/debug/examples/first_fit_bot`.
+// It places the first option that fits anywhere, on the first field it fits on.
+
+use std::io::{self, BufRead, Write};
+
+use karthograph::{
+    bot::{multi_line, Move},
+    grid::{Grid, Placement},
+    shape::Geometry,
+};
+
+fn main() {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+    let mut board: Option<Grid> = None;
+    let mut ruin = false;
+    let mut options: Vec<Geometry> = Vec::default();
+
+    for line in stdin.lock().lines() {
+        let line = line.expect("the referee is still there");
+        let (command, rest) = line.split_once(' ').unwrap_or((&line, ""));
+        match command {
+            "karthograph" => writeln!(stdout, "ready").unwrap(),
+            "board" => board = Some(multi_line(rest).parse().expect("a valid board")),
+            "card" => {
+                ruin = rest.ends_with('1');
+                options.clear();
+            }
+            "option" => {
+                // option <index> <cultivation> <coin> <geometry>
+                let geometry = rest.split(' ').nth(3).expect("an option has a geometry");
+                options.push(multi_line(geometry).parse().expect("a valid geometry"));
+            }
+            "go" => {
+                let grid = board.as_ref().expect("the board comes before go");
+                let chosen = options.iter().enumerate().find_map(|(option, geometry)| {
+                    let placement: Placement =
+                        *grid.legal_placements(geometry, &ruin.into()).first()?;
+                    Some(Move { option, placement })
+                });
+                // every card has an option that fits, otherwise the game replaces it
+                writeln!(stdout, "{}", chosen.expect("an option fits")).unwrap();
+            }
+            "end" => break,
+            _ => {}
+        }
+        stdout.flush().unwrap();
+    }
+}
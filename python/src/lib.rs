@@ -0,0 +1,333 @@
+use std::fs;
+
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::PyObjectProtocol;
+
+use rules::board_code::BoardCode;
+use rules::card::{Card, RuinIndicator};
+use rules::card_pile::CardPile;
+use rules::grid::{Coordinate, Cultivation, Grid};
+use rules::objective::{self, all_objectives, Objective};
+use rules::shape::{Geometry, Orientation, Shape};
+
+fn value_error(reason: &'static str) -> PyErr {
+    PyValueError::new_err(reason)
+}
+
+fn parse_cultivation(symbol: &str) -> PyResult<Cultivation> {
+    let mut symbols = symbol.chars();
+    let cultivation = match (symbols.next(), symbols.next()) {
+        (Some(symbol), None) => Cultivation::from_symbol(symbol),
+        _ => None,
+    };
+    cultivation.ok_or_else(|| value_error("Cultivations are one of v, w, a, f and g"))
+}
+
+/// fields as `(x, y)`, the origin of the grid is its bottom left corner
+fn positions(coords: &[Coordinate]) -> Vec<(i32, i32)> {
+    coords.iter().map(|coord| (coord.x, coord.y)).collect()
+}
+
+/// A geometry written as text, see `Geometry::from_str`
+#[pyclass(name = "Geometry")]
+#[derive(Clone)]
+struct PyGeometry {
+    inner: Geometry,
+}
+
+#[pymethods]
+impl PyGeometry {
+    #[new]
+    fn new(text: &str) -> PyResult<Self> {
+        let inner = text.parse().map_err(value_error)?;
+        Ok(Self { inner })
+    }
+
+    /// the fields relative to the origin
+    fn cells(&self) -> Vec<(i32, i32)> {
+        positions(&self.inner)
+    }
+
+    /// mirrored first, then turned clockwise, like the game does it
+    fn oriented(&self, mirrored: bool, rotations: u8) -> Self {
+        Self {
+            inner: Orientation::new(mirrored, rotations).apply(&self.inner),
+        }
+    }
+
+    /// every distinct orientation as `(mirrored, rotations, geometry)`
+    fn orientations(&self) -> Vec<(bool, u8, Self)> {
+        self.inner
+            .orientations()
+            .into_iter()
+            .map(|(orientation, inner)| {
+                (orientation.mirrored, orientation.rotations, Self { inner })
+            })
+            .collect()
+    }
+}
+
+#[pyproto]
+impl PyObjectProtocol for PyGeometry {
+    fn __str__(&self) -> String {
+        self.inner.to_string()
+    }
+}
+
+/// A geometry with its cultivation, as chosen from a card
+#[pyclass(name = "Shape")]
+#[derive(Clone)]
+struct PyShape {
+    inner: Shape,
+}
+
+#[pymethods]
+impl PyShape {
+    #[new]
+    #[args(ruin = "false", coin = "false")]
+    fn new(
+        geometry: PyRef<PyGeometry>,
+        cultivation: &str,
+        ruin: bool,
+        coin: bool,
+    ) -> PyResult<Self> {
+        Ok(Self {
+            inner: Shape::new(
+                &geometry.inner,
+                &parse_cultivation(cultivation)?,
+                &ruin.into(),
+                coin,
+            ),
+        })
+    }
+
+    #[getter]
+    fn geometry(&self) -> PyGeometry {
+        PyGeometry {
+            inner: self.inner.geometry().clone(),
+        }
+    }
+
+    #[getter]
+    fn cultivation(&self) -> char {
+        self.inner.cultivation().symbol()
+    }
+
+    /// whether it has to be placed on a ruin
+    #[getter]
+    fn ruin(&self) -> bool {
+        *self.inner.ruin()
+    }
+
+    #[getter]
+    fn coin(&self) -> bool {
+        self.inner.has_coin()
+    }
+}
+
+/// A card of the pile, written as text like `Card::from_str` reads it
+#[pyclass(name = "Card")]
+#[derive(Clone)]
+struct PyCard {
+    inner: Card,
+}
+
+#[pymethods]
+impl PyCard {
+    #[new]
+    fn new(text: &str) -> PyResult<Self> {
+        let inner = text.parse().map_err(value_error)?;
+        Ok(Self { inner })
+    }
+
+    #[getter]
+    fn time(&self) -> i32 {
+        self.inner.time()
+    }
+
+    fn is_ruin(&self) -> bool {
+        self.inner.is_ruin()
+    }
+
+    /// the shapes to choose from, a ruin drawn before the card requires them to be placed on a ruin
+    #[args(ruin = "false")]
+    fn options(&self, ruin: bool) -> Vec<PyShape> {
+        self.inner
+            .options(&RuinIndicator::from(ruin))
+            .into_iter()
+            .map(|inner| PyShape { inner })
+            .collect()
+    }
+}
+
+#[pyproto]
+impl PyObjectProtocol for PyCard {
+    fn __str__(&self) -> String {
+        self.inner.to_string()
+    }
+}
+
+/// The map, empty or written as text, see `Grid::from_str`
+#[pyclass(name = "Grid")]
+struct PyGrid {
+    inner: Grid,
+}
+
+#[pymethods]
+impl PyGrid {
+    #[new]
+    #[args(text = "None")]
+    fn new(text: Option<&str>) -> PyResult<Self> {
+        let inner = match text {
+            Some(text) => text.parse().map_err(value_error)?,
+            None => Grid::new(),
+        };
+        Ok(Self { inner })
+    }
+
+    /// the position of a board code, seed and turn are left out
+    #[staticmethod]
+    fn from_code(code: &str) -> PyResult<Self> {
+        let code: BoardCode = code.parse().map_err(value_error)?;
+        Ok(Self { inner: code.grid })
+    }
+
+    fn code(&self) -> String {
+        BoardCode::encode(&self.inner, None, None)
+    }
+
+    fn copy(&self) -> PyResult<Self> {
+        Self::new(Some(&self.inner.to_string()))
+    }
+
+    /// the symbol of the field, as in the text of the grid
+    fn field(&self, x: i32, y: i32) -> PyResult<char> {
+        self.inner
+            .at(&(x, y).into())
+            .map(|field| field.symbol())
+            .map_err(|_| value_error("The field is not part of the grid"))
+    }
+
+    /// whether the shape can be placed with its origin on `x` and `y`
+    fn accepts(&self, shape: PyRef<PyShape>, x: i32, y: i32) -> bool {
+        self.inner
+            .accepts_geometry_at(shape.inner.geometry(), &(x, y).into(), &shape.inner.ruin())
+    }
+
+    /// every distinct placement as `(mirrored, rotations, x, y)`
+    fn legal_placements(&self, shape: PyRef<PyShape>) -> Vec<(bool, u8, i32, i32)> {
+        self.inner
+            .legal_placements(shape.inner.geometry(), &shape.inner.ruin())
+            .iter()
+            .map(|placement| {
+                (
+                    placement.orientation.mirrored,
+                    placement.orientation.rotations,
+                    placement.anchor.x,
+                    placement.anchor.y,
+                )
+            })
+            .collect()
+    }
+
+    /// Places the shape, which is mirrored and turned first.
+    /// Returns the mountains, whose coin got collected, the coin of the shape is not part of them.
+    #[args(mirrored = "false", rotations = "0")]
+    fn place(
+        &mut self,
+        shape: PyRef<PyShape>,
+        x: i32,
+        y: i32,
+        mirrored: bool,
+        rotations: u8,
+    ) -> PyResult<Vec<(i32, i32)>> {
+        let shape = &shape.inner;
+        let oriented = Shape::new(
+            &Orientation::new(mirrored, rotations).apply(shape.geometry()),
+            &shape.cultivation(),
+            &shape.ruin(),
+            shape.has_coin(),
+        );
+        self.inner
+            .try_cultivate(&oriented, &(x, y).into())
+            .map_err(value_error)?;
+        Ok(positions(&self.inner.mountain_coins()))
+    }
+
+    /// the points lost to goblins when a season is scored
+    fn monster_penalty(&self) -> usize {
+        objective::monster_penalty(&self.inner)
+    }
+}
+
+#[pyproto]
+impl PyObjectProtocol for PyGrid {
+    fn __str__(&self) -> String {
+        self.inner.to_string()
+    }
+}
+
+/// A scoring objective, see `objectives`
+#[pyclass(name = "Objective")]
+struct PyObjective {
+    inner: Box<dyn Objective + Send + Sync>,
+}
+
+#[pymethods]
+impl PyObjective {
+    #[getter]
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn score(&self, grid: PyRef<PyGrid>) -> usize {
+        self.inner.score(&grid.inner).points()
+    }
+
+    /// an optimistic maximum, if at most `fields_left` free fields are cultivated from now on
+    fn upper_bound(&self, grid: PyRef<PyGrid>, fields_left: usize) -> usize {
+        self.inner.upper_bound(&grid.inner, fields_left).points()
+    }
+}
+
+#[pyproto]
+impl PyObjectProtocol for PyObjective {
+    fn __str__(&self) -> String {
+        self.inner.name().to_string()
+    }
+}
+
+/// every objective of the game, once
+#[pyfunction]
+fn objectives() -> Vec<PyObjective> {
+    all_objectives()
+        .into_iter()
+        .map(|inner| PyObjective { inner })
+        .collect()
+}
+
+/// the cards of a `.cardpile` file, in the order of the file
+#[pyfunction]
+fn load_cards(path: &str) -> PyResult<Vec<PyCard>> {
+    let text = fs::read_to_string(path).map_err(|err| PyIOError::new_err(err.to_string()))?;
+    let pile: CardPile = text.parse().map_err(value_error)?;
+    Ok(pile
+        .cards
+        .into_iter()
+        .map(|inner| PyCard { inner })
+        .collect())
+}
+
+/// The rules of the game without rendering, to try strategies from python.
+#[pymodule]
+fn karthograph(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<PyGeometry>()?;
+    m.add_class::<PyShape>()?;
+    m.add_class::<PyCard>()?;
+    m.add_class::<PyGrid>()?;
+    m.add_class::<PyObjective>()?;
+    m.add_function(wrap_pyfunction!(objectives, m)?)?;
+    m.add_function(wrap_pyfunction!(load_cards, m)?)?;
+    Ok(())
+}
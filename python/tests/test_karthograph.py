@@ -0,0 +1,75 @@
+from pathlib import Path
+
+import pytest
+
+import karthograph
+
+CARD_PILE = Path(__file__).resolve().parents[2] / "assets" / "content.cardpile"
+
+# the fixture of Metropole in tests/fixtures
+METROPOLE = """
+...........
+...........
+........vv.
+...........
+...........
+...........
+...........
+...........
+..Mvvvvv...
+vv.........
+v..........
+"""
+
+
+def test_cards_are_loaded_from_the_card_pile():
+    cards = karthograph.load_cards(str(CARD_PILE))
+    assert cards
+    for card in cards:
+        assert str(karthograph.Card(str(card))) == str(card)
+        assert card.is_ruin() == (not card.options())
+
+
+def test_shape_cards_have_a_coin_on_the_left():
+    card = karthograph.Card("shape f\n@#\n\n#.\n@#")
+    left, right = card.options(ruin=True)
+    assert left.coin and not right.coin
+    assert left.ruin and right.ruin
+    assert left.cultivation == "f"
+
+
+def test_a_splinter_fits_on_every_free_field():
+    grid = karthograph.Grid()
+    splinter = karthograph.Card("splinter").options()[0]
+    free = str(grid).count(".") + str(grid).count("R")
+    assert len(grid.legal_placements(splinter)) == free
+
+
+def test_placing_cultivates_the_fields():
+    grid = karthograph.Grid()
+    shape = karthograph.Shape(karthograph.Geometry("@#"), "w")
+    x, y = next(
+        (x, y)
+        for (mirrored, rotations, x, y) in grid.legal_placements(shape)
+        if not mirrored and not rotations
+    )
+    assert grid.accepts(shape, x, y)
+    grid.place(shape, x, y)
+    assert grid.field(x, y) == "w"
+    assert not grid.accepts(shape, x, y)
+    with pytest.raises(ValueError):
+        grid.place(shape, x, y)
+
+
+def test_every_objective_scores_the_grid():
+    grid = karthograph.Grid(METROPOLE)
+    scores = {objective.name: objective.score(grid) for objective in karthograph.objectives()}
+    assert len(scores) == 16
+    assert scores["Metropole"] == 3
+    for objective in karthograph.objectives():
+        assert objective.upper_bound(grid, 10) >= objective.score(grid)
+
+
+def test_board_codes_keep_the_grid():
+    grid = karthograph.Grid(METROPOLE)
+    assert str(karthograph.Grid.from_code(grid.code())) == str(grid)
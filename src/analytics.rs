@@ -0,0 +1,205 @@
+use bevy_utils::HashMap;
+use itertools::Itertools;
+use std::cmp::min;
+
+use crate::area::AreaID;
+use crate::bitboard::Bitboard;
+use crate::grid::{Coordinate, Cultivation, Field, Grid};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Occupancy {
+    Free,
+    /// cultivated fields and mountains
+    Filled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Diagonal {
+    /// `x + y` is the same for every field, the first diagonals touch the left and the bottom border
+    Falling,
+    /// `x - y` is the same for every field, the first diagonal is the top left corner
+    Rising,
+}
+
+/// `origin` is the bottom left field of the rectangle
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub origin: Coordinate,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Rect {
+    pub fn area(&self) -> usize {
+        self.width * self.height
+    }
+}
+
+/// connected free fields
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    pub fields: Vec<Coordinate>,
+    pub touches_edge: bool,
+}
+
+impl Region {
+    pub fn size(&self) -> usize {
+        self.fields.len()
+    }
+}
+
+impl Grid {
+    pub fn occupancy(&self, occupancy: Occupancy) -> Bitboard {
+        match occupancy {
+            Occupancy::Free => self.layers().free(),
+            Occupancy::Filled => !self.layers().free(),
+        }
+    }
+
+    /// 0 for fields on the border of the map
+    pub fn distance_to_edge(coord: &Coordinate) -> usize {
+        let last = Self::SIZE as i32 - 1;
+        min(min(coord.x, coord.y), min(last - coord.x, last - coord.y)).max(0) as usize
+    }
+
+    pub fn free_components(&self) -> Vec<Region> {
+        let mut unvisited = self.layers().free();
+        let mut result = Vec::default();
+        while let Some(start) = unvisited.iter().next() {
+            unvisited.unset(&start);
+            let mut stack = vec![start];
+            let mut fields = Vec::default();
+            while let Some(coord) = stack.pop() {
+                fields.push(coord);
+                for neighbor in self.neighbors(&coord) {
+                    if unvisited.contains(&neighbor.position()) {
+                        unvisited.unset(&neighbor.position());
+                        stack.push(neighbor.position());
+                    }
+                }
+            }
+            let touches_edge = fields.iter().any(|pos| Self::distance_to_edge(pos) == 0);
+            result.push(Region {
+                fields,
+                touches_edge,
+            });
+        }
+        result
+    }
+
+    /// free regions of at most `max_size` fields, walled in by filled fields and the border of the map
+    pub fn enclosed_regions(&self, max_size: usize) -> impl Iterator<Item = Region> {
+        self.free_components()
+            .into_iter()
+            .filter(move |region| region.size() <= max_size)
+    }
+
+    pub fn largest_square(&self, occupancy: Occupancy) -> Rect {
+        let fields = self.occupancy(occupancy);
+        // stores the biggest square having this field as top right corner
+        let mut matrix = [[0; Grid::SIZE]; Grid::SIZE];
+        let mut result = Rect::default();
+        for x in 0..Grid::SIZE {
+            for y in 0..Grid::SIZE {
+                if !fields.contains(&(x, y).into()) {
+                    continue;
+                }
+                let left = if x > 0 { matrix[x - 1][y] } else { 0 };
+                let below = if y > 0 { matrix[x][y - 1] } else { 0 };
+                let diagonal = if x > 0 && y > 0 {
+                    matrix[x - 1][y - 1]
+                } else {
+                    0
+                };
+
+                let size = 1 + min(min(left, below), diagonal);
+                matrix[x][y] = size;
+                if size > result.width {
+                    result = Rect {
+                        origin: (x + 1 - size, y + 1 - size).into(),
+                        width: size,
+                        height: size,
+                    };
+                }
+            }
+        }
+        result
+    }
+
+    pub fn largest_rectangle(&self, occupancy: Occupancy) -> Rect {
+        let fields = self.occupancy(occupancy);
+        // number of matching fields straight below and including the current row
+        let mut heights = [0; Grid::SIZE];
+        let mut result = Rect::default();
+        for y in 0..Grid::SIZE {
+            for (x, height) in heights.iter_mut().enumerate() {
+                *height = if fields.contains(&(x, y).into()) {
+                    *height + 1
+                } else {
+                    0
+                };
+            }
+
+            // largest rectangle in the histogram, the stack holds columns of increasing height
+            let mut stack: Vec<usize> = Vec::default();
+            for x in 0..=Grid::SIZE {
+                let height = heights.get(x).copied().unwrap_or(0);
+                while let Some(&top) = stack.last() {
+                    if heights[top] < height {
+                        break;
+                    }
+                    stack.pop();
+                    let left = stack.last().map_or(0, |&column| column + 1);
+                    let rect = Rect {
+                        origin: (left, y + 1 - heights[top]).into(),
+                        width: x - left,
+                        height: heights[top],
+                    };
+                    if rect.area() > result.area() {
+                        result = rect;
+                    }
+                }
+                stack.push(x);
+            }
+        }
+        result
+    }
+
+    /// the fields from the left to the right border, there are `2 * Grid::SIZE - 1` diagonals per direction
+    pub fn diagonal(&self, direction: Diagonal, nth: usize) -> impl Iterator<Item = &Field> {
+        let last = Self::SIZE - 1;
+        (nth.saturating_sub(last)..=min(nth, last)).map(move |x| {
+            let y = match direction {
+                Diagonal::Falling => nth - x,
+                Diagonal::Rising => x + last - nth,
+            };
+            self.at(&(x, y).into()).unwrap()
+        })
+    }
+
+    pub fn diagonals(
+        &self,
+        direction: Diagonal,
+    ) -> impl Iterator<Item = impl Iterator<Item = &Field>> {
+        (0..(2 * Self::SIZE - 1)).map(move |nth| self.diagonal(direction, nth))
+    }
+
+    /// every area with the distinct areas touching it
+    pub fn region_adjacency(&self) -> HashMap<AreaID, Vec<AreaID>> {
+        self.areas()
+            .iter()
+            .map(|(&id, _)| (id, self.neighbor_areas(&id)))
+            .collect()
+    }
+
+    /// the distinct cultivations of the areas touching the area `id`
+    pub fn neighbor_cultivations(&self, id: &AreaID) -> Vec<Cultivation> {
+        self.neighbor_areas(id)
+            .iter()
+            .filter_map(|neighbor| self.area(neighbor))
+            .map(|info| info.kind)
+            .sorted()
+            .dedup()
+            .collect()
+    }
+}
@@ -0,0 +1,208 @@
+use bevy_utils::{HashMap, HashSet};
+use derive_deref::*;
+use std::cmp::Ordering;
+
+use crate::grid::{Coordinate, Cultivation, Grid};
+
+#[derive(Deref, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AreaID(usize);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AreaInfo {
+    pub kind: Cultivation,
+    pub field_coords: Vec<Coordinate>,
+    // fields next to the area, that are not part of it
+    boundary: HashSet<Coordinate>,
+    // may contain ids of areas, that have been merged into others since. Resolve them with `Areas::find`
+    neighbor_areas: HashSet<AreaID>,
+}
+
+impl AreaInfo {
+    fn new(kind: Cultivation, coord: Coordinate) -> Self {
+        Self {
+            kind,
+            field_coords: vec![coord],
+            boundary: Default::default(),
+            neighbor_areas: Default::default(),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.field_coords.len()
+    }
+
+    pub fn boundary(&self) -> &HashSet<Coordinate> {
+        &self.boundary
+    }
+}
+
+impl PartialOrd for AreaInfo {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.size().partial_cmp(&other.size())
+    }
+}
+
+impl Ord for AreaInfo {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.size().cmp(&other.size())
+    }
+}
+
+/// Areas of equally cultivated, connected fields.
+/// They are kept in a disjoint-set, so a placement only merges the touched areas instead of flooding the grid again.
+#[derive(Debug, Clone)]
+pub struct Areas {
+    // `None` for fields without cultivation, roots are their own parent
+    parent: Vec<Option<usize>>,
+    // only roots have an entry
+    infos: HashMap<AreaID, AreaInfo>,
+}
+
+impl Default for Areas {
+    fn default() -> Self {
+        Self {
+            parent: vec![None; Grid::SIZE * Grid::SIZE],
+            infos: Default::default(),
+        }
+    }
+}
+
+impl Areas {
+    fn index(coord: &Coordinate) -> usize {
+        coord.x as usize + coord.y as usize * Grid::SIZE
+    }
+
+    fn coord(index: usize) -> Coordinate {
+        (index % Grid::SIZE, index / Grid::SIZE).into()
+    }
+
+    fn neighbors(index: usize) -> [Option<usize>; 4] {
+        let (x, y) = (index % Grid::SIZE, index / Grid::SIZE);
+        [
+            (x > 0).then(|| index - 1),
+            (x + 1 < Grid::SIZE).then(|| index + 1),
+            (y > 0).then(|| index - Grid::SIZE),
+            (y + 1 < Grid::SIZE).then(|| index + Grid::SIZE),
+        ]
+    }
+
+    fn root(&self, mut index: usize) -> Option<usize> {
+        while let Some(parent) = self.parent[index] {
+            if parent == index {
+                return Some(index);
+            }
+            index = parent;
+        }
+        None
+    }
+
+    /// like `root`, but hangs every field on the way directly below the root
+    fn root_compressed(&mut self, index: usize) -> Option<usize> {
+        let root = self.root(index)?;
+        let mut current = index;
+        while current != root {
+            let next = self.parent[current].unwrap();
+            self.parent[current] = Some(root);
+            current = next;
+        }
+        Some(root)
+    }
+
+    pub fn area_of(&self, coord: &Coordinate) -> Option<AreaID> {
+        self.root(Self::index(coord)).map(AreaID)
+    }
+
+    /// resolves ids of areas, that have been merged in the meantime
+    pub fn find(&self, id: AreaID) -> Option<AreaID> {
+        self.root(*id).map(AreaID)
+    }
+
+    pub fn info(&self, id: &AreaID) -> Option<&AreaInfo> {
+        self.find(*id).and_then(|root| self.infos.get(&root))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&AreaID, &AreaInfo)> {
+        self.infos.iter()
+    }
+
+    /// the distinct areas touching the area `id`
+    pub fn neighbor_areas(&self, id: &AreaID) -> Vec<AreaID> {
+        let root = match self.find(*id) {
+            Some(root) => root,
+            None => return Vec::default(),
+        };
+        let mut result: Vec<AreaID> = self.infos[&root]
+            .neighbor_areas
+            .iter()
+            .filter_map(|&neighbor| self.find(neighbor))
+            .filter(|&neighbor| neighbor != root)
+            .collect();
+        result.sort();
+        result.dedup();
+        result
+    }
+
+    /// adds a freshly cultivated field and merges it with the equally cultivated areas around it
+    pub fn insert(&mut self, coord: &Coordinate, kind: Cultivation) -> AreaID {
+        let index = Self::index(coord);
+        self.parent[index] = Some(index);
+        let id = AreaID(index);
+        let mut info = AreaInfo::new(kind, *coord);
+        let mut same_kind = Vec::new();
+
+        for &neighbor in Self::neighbors(index).iter().flatten() {
+            match self.root_compressed(neighbor).map(AreaID) {
+                Some(other) if self.infos[&other].kind == kind => same_kind.push(other),
+                Some(other) => {
+                    info.boundary.insert(Self::coord(neighbor));
+                    info.neighbor_areas.insert(other);
+                    // `coord` has been on the boundary of `other` before, as it was free
+                    self.infos
+                        .get_mut(&other)
+                        .unwrap()
+                        .neighbor_areas
+                        .insert(id);
+                }
+                None => {
+                    info.boundary.insert(Self::coord(neighbor));
+                }
+            }
+        }
+        self.infos.insert(id, info);
+
+        same_kind
+            .into_iter()
+            .fold(id, |merged, other| self.union(merged, other))
+    }
+
+    fn union(&mut self, lhs: AreaID, rhs: AreaID) -> AreaID {
+        let (lhs, rhs) = (self.find(lhs).unwrap(), self.find(rhs).unwrap());
+        if lhs == rhs {
+            return lhs;
+        }
+        // union by size, only the fields of the smaller area have to be moved
+        let (root, child) = if self.infos[&lhs] >= self.infos[&rhs] {
+            (lhs, rhs)
+        } else {
+            (rhs, lhs)
+        };
+        self.parent[*child] = Some(*root);
+
+        let child_info = self.infos.remove(&child).unwrap();
+        let boundary: Vec<Coordinate> = child_info
+            .boundary
+            .iter()
+            .filter(|coord| self.area_of(coord) != Some(root))
+            .copied()
+            .collect();
+
+        let root_info = self.infos.get_mut(&root).unwrap();
+        for coord in child_info.field_coords.iter() {
+            root_info.boundary.remove(coord);
+        }
+        root_info.boundary.extend(boundary);
+        root_info.field_coords.extend(child_info.field_coords);
+        root_info.neighbor_areas.extend(child_info.neighbor_areas);
+        root
+    }
+}
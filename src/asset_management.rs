@@ -1,8 +1,17 @@
+#[cfg(feature = "game")]
 use bevy::prelude::*;
+#[cfg(feature = "game")]
 use std::collections::HashMap;
 
-use crate::{card_pile::CardPile, GameState};
+#[cfg(feature = "game")]
+use crate::{card_pile::CardPile, config::GameConfig, headless::Headless, GameState};
 
+/// the name of the texture, the rules name them without rendering anything
+pub trait AssetID {
+    fn asset_id(&self) -> &'static str;
+}
+
+#[cfg(feature = "game")]
 const ASSETS: [(&str, &str); 10] = [
     ("mountain", "mountain.png"),
     ("ruin", "ruin.png"),
@@ -16,12 +25,14 @@ const ASSETS: [(&str, &str); 10] = [
     ("coin", "coin.png"),
 ];
 
+#[cfg(feature = "game")]
 #[derive(Default)]
 pub struct UIAssets {
     pub default: Handle<ColorMaterial>,
     pub highlighted: Handle<ColorMaterial>,
 }
 
+#[cfg(feature = "game")]
 #[derive(Default)]
 pub struct AssetManager {
     map: HashMap<&'static str, Handle<ColorMaterial>>,
@@ -29,6 +40,7 @@ pub struct AssetManager {
     pub ui: UIAssets,
     pub font: Handle<Font>,
 }
+#[cfg(feature = "game")]
 impl AssetManager {
     fn insert_asset(&mut self, name: &'static str, handle: Handle<ColorMaterial>) {
         self.map.insert(name, handle);
@@ -41,52 +53,80 @@ impl AssetManager {
         &mut self,
         asset_server: Res<AssetServer>,
         mut materials: ResMut<Assets<ColorMaterial>>,
+        card_pile: &str,
     ) {
         for (name, path) in ASSETS {
             let asset = materials.add(asset_server.load(path).clone().into());
             self.insert_asset(name, asset);
         }
-        self.cards = asset_server.load("content.cardpile");
+        self.cards = asset_server.load(card_pile);
         self.font = asset_server.load("font.ttf");
         self.ui.default = materials.add(Color::SEA_GREEN.into());
         self.ui.highlighted = materials.add(Color::SALMON.into());
     }
 
+    /// plain colors instead of textures and no font, there is nothing to render them anyway
+    pub fn initialize_headless(
+        &mut self,
+        asset_server: Res<AssetServer>,
+        mut materials: ResMut<Assets<ColorMaterial>>,
+        card_pile: &str,
+    ) {
+        for (name, _) in ASSETS {
+            let asset = materials.add(Color::WHITE.into());
+            self.insert_asset(name, asset);
+        }
+        self.cards = asset_server.load(card_pile);
+        self.ui.default = materials.add(Color::SEA_GREEN.into());
+        self.ui.highlighted = materials.add(Color::SALMON.into());
+    }
+
     fn is_loaded(
         &self,
         color_mat: &Res<Assets<ColorMaterial>>,
         card_pile: &Res<Assets<CardPile>>,
-        font: &Res<Assets<Font>>,
+        font: Option<&Res<Assets<Font>>>,
     ) -> bool {
         for (_, handle) in self.map.iter() {
             if color_mat.get(handle).is_none() {
                 return false;
             }
         }
-        card_pile.get(self.cards.clone()).is_some() && font.get(self.font.clone()).is_some()
+        card_pile.get(self.cards.clone()).is_some()
+            && font.map_or(true, |font| font.get(self.font.clone()).is_some())
     }
 }
 
-pub trait AssetID {
-    fn asset_id(&self) -> &'static str;
-}
-
+#[cfg(feature = "game")]
 pub fn init_assets(
     mut asset_manager: ResMut<AssetManager>,
     asset_server: Res<AssetServer>,
     materials: ResMut<Assets<ColorMaterial>>,
+    config: Res<GameConfig>,
+    headless: Option<Res<Headless>>,
 ) {
-    asset_manager.initialize(asset_server, materials);
+    if headless.is_some() {
+        asset_manager.initialize_headless(asset_server, materials, &config.card_pile);
+    } else {
+        asset_manager.initialize(asset_server, materials, &config.card_pile);
+    }
 }
 
+#[cfg(feature = "game")]
 pub fn check_readiness(
     assets: Res<AssetManager>,
     mut state: ResMut<State<GameState>>,
     color_mat: Res<Assets<ColorMaterial>>,
     card_pile: Res<Assets<CardPile>>,
     font: Res<Assets<Font>>,
+    headless: Option<Res<Headless>>,
 ) {
-    if assets.is_loaded(&color_mat, &card_pile, &font) {
+    let font = if headless.is_some() {
+        None
+    } else {
+        Some(&font)
+    };
+    if assets.is_loaded(&color_mat, &card_pile, font) {
         state.set(GameState::SeasonState).unwrap();
     }
 }
@@ -0,0 +1,61 @@
+// Plays a game for a bot, that talks the protocol of `karthograph::bot` over stdin and stdout:
+// referee [--seed N ...] -- <bot executable> [arguments of the bot]
+
+use std::{
+    io::BufReader,
+    process::{self, Command, Stdio},
+};
+
+use karthograph::{
+    bot::{Pipe, Referee},
+    config::GameConfig,
+    headless::HeadlessGame,
+};
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let split = args.iter().position(|arg| arg == "--");
+    let (options, bot) = match split {
+        Some(idx) if idx + 1 < args.len() => (&args[..idx], &args[idx + 1..]),
+        _ => {
+            eprintln!("usage: referee {} -- <bot> [arguments]", GameConfig::USAGE);
+            process::exit(2);
+        }
+    };
+    let config = GameConfig::from_args(options.iter().cloned()).unwrap_or_else(|reason| {
+        eprintln!("{}", reason);
+        process::exit(2);
+    });
+
+    let mut child = Command::new(&bot[0])
+        .args(&bot[1..])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap_or_else(|err| {
+            eprintln!("the bot could not be started: {}", err);
+            process::exit(2);
+        });
+    let pipe = Pipe {
+        output: BufReader::new(child.stdout.take().unwrap()),
+        input: child.stdin.take().unwrap(),
+    };
+
+    let mut game = HeadlessGame::new(config);
+    let result = game.load().and_then(|_| {
+        let mut referee = Referee { game, bot: pipe };
+        referee.run()
+    });
+    match result {
+        Ok(total) => {
+            // the pipes are closed, so the bot can finish on its own
+            let _ = child.wait();
+            println!("{}", total);
+        }
+        Err(reason) => {
+            let _ = child.kill();
+            eprintln!("{}", reason);
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,394 @@
+// Plays the game in a terminal, e.g. over SSH on machines without a display.
+// The rules run in a headless app, every key press is fed into it as a scripted step,
+// so the game behaves exactly like the windowed one.
+
+use std::{
+    fs,
+    io::{self, Stdout, Write},
+};
+
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode},
+    execute, queue,
+    style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor},
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use karthograph::{
+    config::GameConfig,
+    grid::{Coordinate, Cultivation, Field, Grid, Terrain},
+    headless::{HeadlessGame, Step},
+    objective::estimated_fields_in_time,
+    seasons::SeasonType,
+    GameState,
+};
+
+const SEASONS: [SeasonType; 4] = [
+    SeasonType::Spring,
+    SeasonType::Summer,
+    SeasonType::Autumn,
+    SeasonType::Winter,
+];
+// the grid takes two columns per field
+const PANEL_COLUMN: u16 = Grid::SIZE as u16 * 2 + 4;
+const HELP: &str =
+    "1-5 choose  arrows/hjkl move  r/e rotate  m mirror  enter place  F1-F3 skill  s save map  q quit";
+const SVG_PATH: &str = "karthograph.svg";
+
+fn color(cultivation: Cultivation) -> Color {
+    match cultivation {
+        Cultivation::Village => Color::Red,
+        Cultivation::Water => Color::Blue,
+        Cultivation::Farm => Color::Yellow,
+        Cultivation::Forest => Color::Green,
+        Cultivation::Goblin => Color::Magenta,
+    }
+}
+
+/// the symbol of the field as in `Grid::from_str`, with its color
+fn field_symbol(field: &Field) -> (char, Color) {
+    let fg = match (field.cultivation.as_ref(), field.terrain()) {
+        (Some(info), _) => color(*info.cultivation()),
+        (None, Terrain::Mountain(true)) => Color::White,
+        (None, Terrain::Ruin) => Color::DarkYellow,
+        (None, _) => Color::DarkGrey,
+    };
+    (field.symbol(), fg)
+}
+
+fn season_name(season: &SeasonType) -> &'static str {
+    match season {
+        SeasonType::Spring => "Spring",
+        SeasonType::Summer => "Summer",
+        SeasonType::Autumn => "Autumn",
+        SeasonType::Winter => "Winter",
+    }
+}
+
+struct Tui {
+    game: HeadlessGame,
+    cursor: Coordinate,
+    message: Option<&'static str>,
+}
+
+impl Tui {
+    fn run(&mut self, out: &mut Stdout) -> crossterm::Result<()> {
+        loop {
+            self.draw(out)?;
+            let key = match event::read()? {
+                Event::Key(key) => key.code,
+                _ => continue,
+            };
+            self.message = None;
+
+            let steps = match (self.game.state(), key) {
+                (_, KeyCode::Esc) | (_, KeyCode::Char('q')) => return Ok(()),
+                (_, KeyCode::Char('s')) => {
+                    self.message = Some(match fs::write(SVG_PATH, self.game.svg()) {
+                        Ok(()) => "The map was saved as karthograph.svg",
+                        Err(_) => "The map could not be saved",
+                    });
+                    Vec::default()
+                }
+                (GameState::SeasonScoreState, KeyCode::Enter | KeyCode::Char(' ')) => {
+                    vec![Step::Continue]
+                }
+                (GameState::SeasonState, key) => self.season_steps(key),
+                _ => Vec::default(),
+            };
+            if !steps.is_empty() {
+                self.message = self.game.play(steps).err();
+            }
+        }
+    }
+
+    fn season_steps(&mut self, key: KeyCode) -> Vec<Step> {
+        let delta = match key {
+            KeyCode::Left | KeyCode::Char('h') => Some((-1, 0)),
+            KeyCode::Right | KeyCode::Char('l') => Some((1, 0)),
+            KeyCode::Up | KeyCode::Char('k') => Some((0, 1)),
+            KeyCode::Down | KeyCode::Char('j') => Some((0, -1)),
+            _ => None,
+        };
+        if let Some(delta) = delta {
+            let last = Grid::SIZE as i32 - 1;
+            let moved = self.cursor + Coordinate::from(delta);
+            self.cursor = (moved.x.clamp(0, last), moved.y.clamp(0, last)).into();
+            return if self.game.shape().is_some() {
+                vec![Step::Move(self.cursor)]
+            } else {
+                Vec::default()
+            };
+        }
+
+        match key {
+            KeyCode::Char(digit @ '1'..='5') => {
+                let option = digit as usize - '1' as usize;
+                vec![Step::Choose(option), Step::Move(self.cursor)]
+            }
+            KeyCode::Char('r') => vec![Step::RotateClockwise],
+            KeyCode::Char('e') => vec![Step::RotateCounterClockwise],
+            KeyCode::Char('m') => vec![Step::Mirror],
+            KeyCode::Enter | KeyCode::Char(' ') => match self.game.shape() {
+                Some(shape) if shape.fits(self.game.grid(), &self.cursor) => {
+                    vec![Step::Move(self.cursor), Step::Place]
+                }
+                Some(_) => {
+                    self.message = Some("The shape does not fit here");
+                    Vec::default()
+                }
+                None => {
+                    self.message = Some("Choose an option of the card first");
+                    Vec::default()
+                }
+            },
+            KeyCode::F(n @ 1..=3) => vec![Step::BuySkill(n as usize - 1)],
+            _ => Vec::default(),
+        }
+    }
+
+    fn draw(&mut self, out: &mut Stdout) -> crossterm::Result<()> {
+        queue!(out, Clear(ClearType::All))?;
+        let season = self.game.season();
+        let mut title = format!(
+            "Karthograph - {} {}/{}",
+            season_name(season.season_type()),
+            season.passed_time(),
+            season.total_time()
+        );
+        let players = self.game.players();
+        if players.count() > 1 {
+            title += &format!(" - player {}/{}", players.current() + 1, players.count());
+        }
+        queue!(out, MoveTo(0, 0), Print(title))?;
+
+        self.draw_grid(out, 2)?;
+        let mut row = 2;
+        match self.game.state() {
+            GameState::SeasonState => row = self.draw_card(out, row)?,
+            GameState::SeasonScoreState => {
+                queue!(
+                    out,
+                    MoveTo(PANEL_COLUMN, row),
+                    Print("The season is over, press enter to continue")
+                )?;
+                row += 2;
+            }
+            GameState::End => {
+                let total = self.game.objectives().total_score();
+                queue!(
+                    out,
+                    MoveTo(PANEL_COLUMN, row),
+                    Print(format!("The game is over with {} points", total))
+                )?;
+                row += 2;
+            }
+            GameState::Loading => {}
+        }
+        row = self.draw_skills(out, row)?;
+        row = self.draw_objectives(out, row + 1)?;
+
+        // below the grid and the panel
+        let bottom = row.max(Grid::SIZE as u16 + 2) + 1;
+        if let Some(message) = self.message {
+            queue!(
+                out,
+                MoveTo(0, bottom),
+                SetForegroundColor(Color::Red),
+                Print(message),
+                ResetColor
+            )?;
+        }
+        // pasted into `--code`, it continues the game from here
+        let code = format!("code: {}", self.game.board_code());
+        queue!(
+            out,
+            MoveTo(0, bottom + 1),
+            Print(code),
+            MoveTo(0, bottom + 2),
+            Print(HELP)
+        )?;
+        out.flush()
+    }
+
+    fn draw_grid(&mut self, out: &mut Stdout, top: u16) -> crossterm::Result<()> {
+        let shape = self.game.shape();
+        let grid = self.game.grid();
+        let fits = shape
+            .as_ref()
+            .map_or(false, |shape| shape.fits(grid, &self.cursor));
+        let covered: Vec<Coordinate> = match &shape {
+            Some(shape) => shape
+                .geometry()
+                .iter()
+                .map(|&offset| self.cursor + offset)
+                .collect(),
+            None => vec![self.cursor],
+        };
+
+        for field in grid.all() {
+            let pos = field.position();
+            let (mut symbol, mut fg) = field_symbol(field);
+            let highlighted = covered.contains(&pos);
+            if let (true, Some(shape)) = (highlighted, &shape) {
+                symbol = '#';
+                fg = if fits {
+                    color(shape.cultivation())
+                } else {
+                    Color::DarkRed
+                };
+            }
+            let row = top + (Grid::SIZE as i32 - 1 - pos.y) as u16;
+            queue!(out, MoveTo(pos.x as u16 * 2, row), SetForegroundColor(fg))?;
+            if highlighted {
+                queue!(out, SetAttribute(Attribute::Reverse))?;
+            }
+            queue!(
+                out,
+                Print(symbol),
+                SetAttribute(Attribute::Reset),
+                ResetColor
+            )?;
+        }
+        Ok(())
+    }
+
+    /// returns the next free row
+    fn draw_card(&mut self, out: &mut Stdout, mut row: u16) -> crossterm::Result<u16> {
+        let card = match self.game.card() {
+            Some(card) => card,
+            None => return Ok(row),
+        };
+        let options = self.game.options();
+        let ruin = options.first().map_or(false, |shape| *shape.ruin());
+        let title = format!(
+            "Card: {} time{}",
+            card.time(),
+            if ruin { ", on a ruin" } else { "" }
+        );
+        queue!(out, MoveTo(PANEL_COLUMN, row), Print(title))?;
+        row += 1;
+
+        for (idx, option) in options.iter().enumerate() {
+            queue!(
+                out,
+                MoveTo(PANEL_COLUMN, row),
+                Print(format!("{}) ", idx + 1)),
+                SetForegroundColor(color(option.cultivation())),
+                Print(format!("{:?}", option.cultivation())),
+                ResetColor,
+                Print(if option.has_coin() { " + coin" } else { "" })
+            )?;
+            row += 1;
+            // `@` is the field under the cursor
+            for line in option.geometry().to_string().lines() {
+                queue!(out, MoveTo(PANEL_COLUMN + 3, row), Print(line))?;
+                row += 1;
+            }
+        }
+        Ok(row + 1)
+    }
+
+    /// returns the next free row
+    fn draw_skills(&mut self, out: &mut Stdout, mut row: u16) -> crossterm::Result<u16> {
+        let coins = self.game.objectives().coins();
+        queue!(
+            out,
+            MoveTo(PANEL_COLUMN, row),
+            Print(format!("Coins: {}", coins))
+        )?;
+        row += 1;
+        let skills = self.game.skills();
+        for (idx, skill) in skills.cards().iter().enumerate() {
+            let active = if skills.active() == Some(idx) {
+                " *"
+            } else {
+                ""
+            };
+            queue!(
+                out,
+                MoveTo(PANEL_COLUMN, row),
+                Print(format!(
+                    "F{} {} ({}){}",
+                    idx + 1,
+                    skill.name(),
+                    skill.cost(),
+                    active
+                ))
+            )?;
+            row += 1;
+        }
+        Ok(row)
+    }
+
+    /// the objectives of each season, with the scores or what can still be reached, returns the next free row
+    fn draw_objectives(&mut self, out: &mut Stdout, mut row: u16) -> crossterm::Result<u16> {
+        let objectives = self.game.objectives();
+        let season = self.game.season();
+        let grid = self.game.grid();
+        for season_type in SEASONS.iter() {
+            let line = match objectives.season_score(season_type) {
+                Some(score) => format!(
+                    "{}: {} {}, {} {}, coins {}, monsters -{} = {}",
+                    season_name(season_type),
+                    score.a.0,
+                    score.a.1.points(),
+                    score.b.0,
+                    score.b.1.points(),
+                    score.coin_count,
+                    score.monster_penalty,
+                    score.total()
+                ),
+                None => {
+                    let (a, b) = objectives.objectives_for_season(season_type);
+                    let (current_a, current_b) = objectives.current_scores(season_type);
+                    let fields_left = season
+                        .time_until_scoring(season_type)
+                        .map_or(0, estimated_fields_in_time);
+                    let (max_a, max_b) = objectives.upper_bounds(season_type, grid, fields_left);
+                    format!(
+                        "{}: {} {} (about {}), {} {} (about {})",
+                        season_name(season_type),
+                        a.name(),
+                        current_a.map_or(0, |score| score.points()),
+                        max_a.points(),
+                        b.name(),
+                        current_b.map_or(0, |score| score.points()),
+                        max_b.points()
+                    )
+                }
+            };
+            queue!(out, MoveTo(PANEL_COLUMN, row), Print(line))?;
+            row += 1;
+        }
+        Ok(row)
+    }
+}
+
+fn main() -> crossterm::Result<()> {
+    let config = match GameConfig::from_args(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(reason) => {
+            eprintln!("{}\nusage: tui {}", reason, GameConfig::USAGE);
+            return Ok(());
+        }
+    };
+    let mut game = HeadlessGame::new(config);
+    if let Err(reason) = game.load() {
+        eprintln!("{}", reason);
+        return Ok(());
+    }
+
+    let mut out = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(out, EnterAlternateScreen, Hide)?;
+    let result = Tui {
+        game,
+        cursor: (Grid::SIZE / 2, Grid::SIZE / 2).into(),
+        message: None,
+    }
+    .run(&mut out);
+    execute!(out, Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
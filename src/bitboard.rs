@@ -0,0 +1,285 @@
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not};
+
+use crate::card::RuinIndicator;
+use crate::grid::{Coordinate, Cultivation, Grid};
+use crate::shape::Geometry;
+
+const FIELDS: usize = Grid::SIZE * Grid::SIZE;
+// 2 words for the 11x11 grid, bigger grids just take more words
+const WORDS: usize = (FIELDS + 63) / 64;
+
+/// One bit per field, the field at (x, y) is bit `x + y * Grid::SIZE`.
+/// Bits beyond the last field are always unset.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bitboard([u64; WORDS]);
+
+const fn fields_in(columns: (usize, usize), rows: (usize, usize)) -> Bitboard {
+    let mut words = [0; WORDS];
+    let mut y = rows.0;
+    while y < rows.1 {
+        let mut x = columns.0;
+        while x < columns.1 {
+            let index = x + y * Grid::SIZE;
+            words[index / 64] |= 1 << (index % 64);
+            x += 1;
+        }
+        y += 1;
+    }
+    Bitboard(words)
+}
+
+const fn column_table(from: bool) -> [Bitboard; Grid::SIZE + 1] {
+    let mut table = [Bitboard([0; WORDS]); Grid::SIZE + 1];
+    let mut nth = 0;
+    while nth <= Grid::SIZE {
+        table[nth] = if from {
+            fields_in((nth, Grid::SIZE), (0, Grid::SIZE))
+        } else {
+            fields_in((0, nth), (0, Grid::SIZE))
+        };
+        nth += 1;
+    }
+    table
+}
+
+const fn row_table(from: bool) -> [Bitboard; Grid::SIZE + 1] {
+    let mut table = [Bitboard([0; WORDS]); Grid::SIZE + 1];
+    let mut nth = 0;
+    while nth <= Grid::SIZE {
+        table[nth] = if from {
+            fields_in((0, Grid::SIZE), (nth, Grid::SIZE))
+        } else {
+            fields_in((0, Grid::SIZE), (0, nth))
+        };
+        nth += 1;
+    }
+    table
+}
+
+// `COLUMNS_FROM[n]` holds the columns n.., `COLUMNS_UNTIL[n]` the columns ..n, same for rows
+const COLUMNS_FROM: [Bitboard; Grid::SIZE + 1] = column_table(true);
+const COLUMNS_UNTIL: [Bitboard; Grid::SIZE + 1] = column_table(false);
+const ROWS_FROM: [Bitboard; Grid::SIZE + 1] = row_table(true);
+const ROWS_UNTIL: [Bitboard; Grid::SIZE + 1] = row_table(false);
+
+impl Bitboard {
+    pub const EMPTY: Self = Self([0; WORDS]);
+    pub const FULL: Self = fields_in((0, Grid::SIZE), (0, Grid::SIZE));
+
+    fn index(coord: &Coordinate) -> Option<usize> {
+        if coord.x < 0
+            || coord.y < 0
+            || coord.x >= Grid::SIZE as i32
+            || coord.y >= Grid::SIZE as i32
+        {
+            None
+        } else {
+            Some(coord.x as usize + coord.y as usize * Grid::SIZE)
+        }
+    }
+
+    pub fn set(&mut self, coord: &Coordinate) {
+        if let Some(index) = Self::index(coord) {
+            self.0[index / 64] |= 1 << (index % 64);
+        }
+    }
+
+    pub fn unset(&mut self, coord: &Coordinate) {
+        if let Some(index) = Self::index(coord) {
+            self.0[index / 64] &= !(1 << (index % 64));
+        }
+    }
+
+    pub fn contains(&self, coord: &Coordinate) -> bool {
+        Self::index(coord).map_or(false, |index| self.0[index / 64] & (1 << (index % 64)) != 0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|&word| word == 0)
+    }
+
+    pub fn count(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Coordinate> {
+        let words = self.0;
+        (0..FIELDS)
+            .filter(move |index| words[index / 64] & (1 << (index % 64)) != 0)
+            .map(|index| (index % Grid::SIZE, index / Grid::SIZE).into())
+    }
+
+    /// bit `i` of the result is bit `i + delta` of `self`
+    pub fn shifted(&self, delta: isize) -> Self {
+        let mut words = [0; WORDS];
+        let amount = delta.unsigned_abs();
+        let (word_shift, bit_shift) = (amount / 64, amount % 64);
+        for (i, word) in words.iter_mut().enumerate() {
+            if delta >= 0 {
+                // towards lower indices
+                let src = i + word_shift;
+                if src < WORDS {
+                    *word = self.0[src] >> bit_shift;
+                    if bit_shift > 0 && src + 1 < WORDS {
+                        *word |= self.0[src + 1] << (64 - bit_shift);
+                    }
+                }
+            } else if i >= word_shift {
+                let src = i - word_shift;
+                *word = self.0[src] << bit_shift;
+                if bit_shift > 0 && src > 0 {
+                    *word |= self.0[src - 1] >> (64 - bit_shift);
+                }
+            }
+        }
+        Self(words) & Self::FULL
+    }
+
+    /// anchors, for which `anchor + offset` is still on the grid
+    fn window(offset: &Coordinate) -> Self {
+        let range = |delta: i32| {
+            let size = Grid::SIZE as i32;
+            (
+                (-delta).clamp(0, size) as usize,
+                (size - delta).clamp(0, size) as usize,
+            )
+        };
+        let (x_from, x_until) = range(offset.x);
+        let (y_from, y_until) = range(offset.y);
+        COLUMNS_FROM[x_from] & COLUMNS_UNTIL[x_until] & ROWS_FROM[y_from] & ROWS_UNTIL[y_until]
+    }
+
+    /// bit `anchor` of the result is set if every `anchor + offset` is set in `self`
+    pub fn anchors_covered_by(&self, geom: &Geometry) -> Self {
+        geom.iter().fold(Self::FULL, |anchors, offset| {
+            anchors & self.at_offset(offset)
+        })
+    }
+
+    /// bit `anchor` of the result is set if any `anchor + offset` is set in `self`
+    pub fn anchors_touching(&self, geom: &Geometry) -> Self {
+        geom.iter().fold(Self::EMPTY, |anchors, offset| {
+            anchors | self.at_offset(offset)
+        })
+    }
+
+    fn at_offset(&self, offset: &Coordinate) -> Self {
+        let delta = offset.x as isize + offset.y as isize * Grid::SIZE as isize;
+        self.shifted(delta) & Self::window(offset)
+    }
+}
+
+impl BitAnd for Bitboard {
+    type Output = Self;
+    fn bitand(mut self, rhs: Self) -> Self::Output {
+        self &= rhs;
+        self
+    }
+}
+
+impl BitAndAssign for Bitboard {
+    fn bitand_assign(&mut self, rhs: Self) {
+        for (word, rhs) in self.0.iter_mut().zip(rhs.0.iter()) {
+            *word &= rhs;
+        }
+    }
+}
+
+impl BitOr for Bitboard {
+    type Output = Self;
+    fn bitor(mut self, rhs: Self) -> Self::Output {
+        self |= rhs;
+        self
+    }
+}
+
+impl BitOrAssign for Bitboard {
+    fn bitor_assign(&mut self, rhs: Self) {
+        for (word, rhs) in self.0.iter_mut().zip(rhs.0.iter()) {
+            *word |= rhs;
+        }
+    }
+}
+
+impl Not for Bitboard {
+    type Output = Self;
+    fn not(self) -> Self::Output {
+        let mut words = self.0;
+        for word in words.iter_mut() {
+            *word = !*word;
+        }
+        Self(words) & Self::FULL
+    }
+}
+
+/// The grid as a stack of bitboards, kept up to date next to the fields for fast placement checks
+#[derive(Debug, Clone)]
+pub struct Layers {
+    free: Bitboard,
+    mountains: Bitboard,
+    ruins: Bitboard,
+    cultivations: [Bitboard; 5],
+}
+
+impl Default for Layers {
+    fn default() -> Self {
+        Self {
+            free: Bitboard::FULL,
+            mountains: Bitboard::EMPTY,
+            ruins: Bitboard::EMPTY,
+            cultivations: [Bitboard::EMPTY; 5],
+        }
+    }
+}
+
+impl Layers {
+    pub fn free(&self) -> Bitboard {
+        self.free
+    }
+
+    pub fn mountains(&self) -> Bitboard {
+        self.mountains
+    }
+
+    pub fn ruins(&self) -> Bitboard {
+        self.ruins
+    }
+
+    pub fn cultivation(&self, cultivation: Cultivation) -> Bitboard {
+        self.cultivations[cultivation as usize]
+    }
+
+    pub fn add_mountain(&mut self, coord: &Coordinate) {
+        self.mountains.set(coord);
+        self.free.unset(coord);
+    }
+
+    pub fn add_ruin(&mut self, coord: &Coordinate) {
+        self.ruins.set(coord);
+    }
+
+    pub fn cultivate(&mut self, coord: &Coordinate, cultivation: Cultivation) {
+        self.uncultivate(coord);
+        self.free.unset(coord);
+        self.cultivations[cultivation as usize].set(coord);
+    }
+
+    pub fn uncultivate(&mut self, coord: &Coordinate) {
+        for layer in self.cultivations.iter_mut() {
+            layer.unset(coord);
+        }
+        if !self.mountains.contains(coord) {
+            self.free.set(coord);
+        }
+    }
+
+    /// every anchor `geom` can be placed at without rotating or mirroring it
+    pub fn legal_anchors(&self, geom: &Geometry, ruin: &RuinIndicator) -> Bitboard {
+        let anchors = self.free.anchors_covered_by(geom);
+        if **ruin {
+            anchors & self.ruins.anchors_touching(geom)
+        } else {
+            anchors
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::grid::{Field, Grid};
+
+const VERSION: u8 = 1;
+const HAS_SEED: u8 = 0b01;
+const HAS_TURN: u8 = 0b10;
+// every field is one of these, as written by `Grid::from_str`
+const SYMBOLS: [char; 14] = [
+    '.', 'R', 'M', 'm', 'v', 'w', 'a', 'f', 'g', 'V', 'W', 'A', 'F', 'G',
+];
+// RFC 4648, without padding. Unlike base64 it survives being read out loud or typed in lowercase
+const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// A position as short text, meant to be pasted into chats:
+/// every field takes four bits, followed by the seed and the number of cards played so far, if known.
+/// The bytes are written in base32.
+///
+/// Loading a code with a turn draws the cards played before it again, but unplacable ones are not swapped
+/// for a splinter and the seasons before are not scored. The season can therefore be further along
+/// than in the original game, and the points of the earlier seasons are missing from the total.
+pub struct BoardCode {
+    pub grid: Grid,
+    pub seed: Option<u64>,
+    /// the number of cards played before this position
+    pub turn: Option<u16>,
+}
+
+impl BoardCode {
+    pub fn encode(grid: &Grid, seed: Option<u64>, turn: Option<u16>) -> String {
+        let mut flags = 0;
+        if seed.is_some() {
+            flags |= HAS_SEED;
+        }
+        if turn.is_some() {
+            flags |= HAS_TURN;
+        }
+        let mut bytes = vec![VERSION, flags];
+
+        let nibbles: Vec<u8> = grid.all().map(nibble).collect();
+        bytes.extend(
+            nibbles
+                .chunks(2)
+                .map(|pair| (pair[0] << 4) | pair.get(1).copied().unwrap_or(0)),
+        );
+        if let Some(seed) = seed {
+            bytes.extend_from_slice(&seed.to_be_bytes());
+        }
+        if let Some(turn) = turn {
+            bytes.extend_from_slice(&turn.to_be_bytes());
+        }
+        base32(&bytes)
+    }
+}
+
+fn nibble(field: &Field) -> u8 {
+    SYMBOLS
+        .iter()
+        .position(|&symbol| symbol == field.symbol())
+        .expect("every field has one of the symbols") as u8
+}
+
+impl fmt::Display for BoardCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Self::encode(&self.grid, self.seed, self.turn))
+    }
+}
+
+/// whitespace is skipped and lowercase letters are accepted, so codes can be split over lines or typed by hand
+impl FromStr for BoardCode {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = from_base32(s)?;
+        let fields = Grid::SIZE * Grid::SIZE;
+        let grid_len = (fields + 1) / 2;
+        let (header, rest) = match bytes.as_slice() {
+            [VERSION, flags, rest @ ..] => (*flags, rest),
+            [_, _, ..] => return Err("The board code is of an unknown version"),
+            _ => return Err("The board code is too short"),
+        };
+        let expected = grid_len
+            + if header & HAS_SEED != 0 { 8 } else { 0 }
+            + if header & HAS_TURN != 0 { 2 } else { 0 };
+        if rest.len() != expected {
+            return Err("The board code has the wrong length");
+        }
+        let (fields_bytes, mut rest) = rest.split_at(grid_len);
+
+        // rebuilt as text, so the board is checked like any other
+        let symbols = fields_bytes
+            .iter()
+            .flat_map(|byte| [byte >> 4, byte & 0x0f])
+            .take(fields)
+            .map(|nibble| {
+                SYMBOLS
+                    .get(nibble as usize)
+                    .copied()
+                    .ok_or("The board code contains an unknown field")
+            })
+            .collect::<Result<Vec<char>, _>>()?;
+        let text: Vec<String> = symbols
+            .chunks(Grid::SIZE)
+            .rev()
+            .map(|row| row.iter().collect())
+            .collect();
+        let grid = text.join("\n").parse()?;
+
+        let mut seed = None;
+        if header & HAS_SEED != 0 {
+            let (bytes, tail) = rest.split_at(8);
+            seed = Some(u64::from_be_bytes([
+                bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
+            ]));
+            rest = tail;
+        }
+        let turn = if header & HAS_TURN != 0 {
+            Some(u16::from_be_bytes([rest[0], rest[1]]))
+        } else {
+            None
+        };
+        Ok(Self { grid, seed, turn })
+    }
+}
+
+fn base32(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity((bytes.len() * 8 + 4) / 5);
+    let mut buffer: u16 = 0;
+    let mut bits = 0;
+    for &byte in bytes {
+        buffer = (buffer << 8) | byte as u16;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            result.push(ALPHABET[(buffer >> bits) as usize & 0x1f] as char);
+        }
+    }
+    if bits > 0 {
+        result.push(ALPHABET[(buffer << (5 - bits)) as usize & 0x1f] as char);
+    }
+    result
+}
+
+fn from_base32(text: &str) -> Result<Vec<u8>, &'static str> {
+    let mut result = Vec::with_capacity(text.len() * 5 / 8);
+    let mut buffer: u16 = 0;
+    let mut bits = 0;
+    for symbol in text.chars().filter(|symbol| !symbol.is_whitespace()) {
+        let value = ALPHABET
+            .iter()
+            .position(|&letter| letter as char == symbol.to_ascii_uppercase())
+            .ok_or("A board code consists of letters and the digits 2 to 7")?;
+        buffer = (buffer << 5) | value as u16;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            result.push((buffer >> bits) as u8);
+        }
+    }
+    // the remaining bits only pad the last byte
+    Ok(result)
+}
@@ -0,0 +1,269 @@
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+use crate::grid::Placement;
+use crate::shape::Orientation;
+#[cfg(feature = "game")]
+use crate::{
+    headless::{HeadlessGame, Step},
+    shape::Geometry,
+    GameState,
+};
+
+/// A line based protocol between the game, acting as referee, and a bot, much like UCI for chess engines.
+/// Boards and geometries are written as text (see `Grid::from_str` and `Geometry::from_str`) with `/` between the rows.
+///
+/// ```text
+/// referee: karthograph 1
+/// bot:     ready                         (lines before are ignored, e.g. the name of the bot)
+/// referee: objectives <A>|<B>|<C>|<D>    (spring scores A and B, summer B and C, autumn C and D, winter D and A)
+///
+/// for every card:
+/// referee: season <Spring|Summer|Autumn|Winter> <passed time> <time of the season>
+/// referee: coins <coins>
+/// referee: board <row>/<row>/...        (the top row first)
+/// referee: card <time> <ruin 0|1>
+/// referee: option <index> <cultivation> <coin 0|1> <geometry>
+/// referee: go
+/// bot:     move <option> <mirrored 0|1> <clockwise rotations> <x> <y>
+/// referee: illegal <reason>              (the bot has to answer again)
+///
+/// at the end of every season and the game:
+/// referee: scored <season> <a> <b> <coins> <monsters> <total>
+/// referee: end <total points>
+/// ```
+/// The oriented geometry is placed with its origin on `x` and `y`.
+pub const PROTOCOL: &str = "karthograph 1";
+/// illegal moves in a row, after which the bot loses the game
+pub const MAX_ATTEMPTS: usize = 3;
+
+/// the answer of the bot to `go`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub option: usize,
+    pub placement: Placement,
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "move {} {} {} {} {}",
+            self.option,
+            self.placement.orientation.mirrored as u8,
+            self.placement.orientation.rotations,
+            self.placement.anchor.x,
+            self.placement.anchor.y
+        )
+    }
+}
+
+impl FromStr for Move {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        match words.as_slice() {
+            ["move", option, mirrored, rotations, x, y] => {
+                let number = "A move consists of numbers";
+                let mirrored = match *mirrored {
+                    "0" => false,
+                    "1" => true,
+                    _ => return Err("Mirrored is either 0 or 1"),
+                };
+                let rotations: u8 = rotations.parse().map_err(|_| number)?;
+                let x: i32 = x.parse().map_err(|_| number)?;
+                let y: i32 = y.parse().map_err(|_| number)?;
+                Ok(Self {
+                    option: option.parse().map_err(|_| number)?,
+                    placement: Placement {
+                        orientation: Orientation::new(mirrored, rotations),
+                        anchor: (x, y).into(),
+                    },
+                })
+            }
+            _ => Err("Expected `move <option> <mirrored> <rotations> <x> <y>`"),
+        }
+    }
+}
+
+/// rows separated by `/`, so boards and geometries fit on one line
+pub fn single_line(text: &str) -> String {
+    text.lines().collect::<Vec<_>>().join("/")
+}
+
+/// reads what `single_line` writes
+pub fn multi_line(line: &str) -> String {
+    line.replace('/', "\n")
+}
+
+#[cfg(feature = "game")]
+/// Checks the move against the rules and plays it.
+/// Nothing is played, unless the move is legal.
+pub fn play_move(game: &mut HeadlessGame, chosen: &Move) -> Result<(), &'static str> {
+    let options = game.options();
+    let shape = options
+        .get(chosen.option)
+        .ok_or("The card has no such option")?;
+    let geometry: Geometry = chosen.placement.orientation.apply(shape.geometry());
+    if !game
+        .grid()
+        .accepts_geometry_at(&geometry, &chosen.placement.anchor, &shape.ruin())
+    {
+        return Err("The shape cannot be placed there");
+    }
+
+    // turned like `Orientation::apply` does it
+    let mut steps = vec![Step::Choose(chosen.option)];
+    if chosen.placement.orientation.mirrored {
+        steps.push(Step::Mirror);
+    }
+    for _ in 0..chosen.placement.orientation.rotations {
+        steps.push(Step::RotateClockwise);
+    }
+    steps.extend([Step::Move(chosen.placement.anchor), Step::Place]);
+    game.play(steps)?;
+    if game.shape().is_some() {
+        return Err("The game did not accept the move");
+    }
+    Ok(())
+}
+
+/// One end of the protocol. The referee talks to bots through it, no matter whether they run as process or in a test.
+pub trait Connection {
+    fn send(&mut self, line: &str) -> Result<(), &'static str>;
+    fn receive(&mut self) -> Result<String, &'static str>;
+}
+
+/// a bot, whose output is read from `R` and whose input is written to `W`, e.g. the pipes of a child process
+pub struct Pipe<R: BufRead, W: Write> {
+    pub output: R,
+    pub input: W,
+}
+
+impl<R: BufRead, W: Write> Connection for Pipe<R, W> {
+    fn send(&mut self, line: &str) -> Result<(), &'static str> {
+        writeln!(self.input, "{}", line)
+            .and_then(|_| self.input.flush())
+            .map_err(|_| "The bot does not listen anymore")
+    }
+
+    fn receive(&mut self) -> Result<String, &'static str> {
+        let mut line = String::default();
+        match self.output.read_line(&mut line) {
+            Ok(0) | Err(_) => Err("The bot does not answer anymore"),
+            Ok(_) => Ok(line.trim().to_string()),
+        }
+    }
+}
+
+#[cfg(feature = "game")]
+/// Plays a game for a bot. Every move is checked against the rules, before it is played.
+pub struct Referee<C: Connection> {
+    pub game: HeadlessGame,
+    pub bot: C,
+}
+
+#[cfg(feature = "game")]
+impl<C: Connection> Referee<C> {
+    /// plays the loaded game to its end and returns the total points
+    pub fn run(&mut self) -> Result<isize, &'static str> {
+        if self.game.players().count() != 1 {
+            return Err("The referee plays a single bot on a single map");
+        }
+        self.bot.send(PROTOCOL)?;
+        while self.bot.receive()? != "ready" {}
+        let names: Vec<&str> = self
+            .game
+            .objectives()
+            .objectives()
+            .map(|objective| objective.name())
+            .collect();
+        self.bot.send(&format!("objectives {}", names.join("|")))?;
+
+        loop {
+            match self.game.state() {
+                GameState::SeasonState => self.turn()?,
+                GameState::SeasonScoreState => {
+                    self.send_season_score()?;
+                    self.game.play(vec![Step::Continue])?;
+                }
+                GameState::End => {
+                    let total = self.game.objectives().total_score();
+                    self.bot.send(&format!("end {}", total))?;
+                    return Ok(total);
+                }
+                GameState::Loading => return Err("The game has to be loaded first"),
+            }
+        }
+    }
+
+    fn turn(&mut self) -> Result<(), &'static str> {
+        for line in self.turn_messages()? {
+            self.bot.send(&line)?;
+        }
+        for _ in 0..MAX_ATTEMPTS {
+            let reply = self.bot.receive()?;
+            let result = reply
+                .parse::<Move>()
+                .and_then(|chosen| play_move(&mut self.game, &chosen));
+            match result {
+                Ok(()) => return Ok(()),
+                Err(reason) => self.bot.send(&format!("illegal {}", reason))?,
+            }
+        }
+        Err("The bot made too many illegal moves")
+    }
+
+    fn turn_messages(&mut self) -> Result<Vec<String>, &'static str> {
+        let card = self.game.card().ok_or("There is no card on the table")?;
+        let options = self.game.options();
+        let season = self.game.season();
+        let mut lines = vec![
+            format!(
+                "season {:?} {} {}",
+                season.season_type(),
+                season.passed_time(),
+                season.total_time()
+            ),
+            format!("coins {}", self.game.objectives().coins()),
+            format!("board {}", single_line(&self.game.grid().to_string())),
+            format!(
+                "card {} {}",
+                card.time(),
+                options.first().map_or(false, |shape| *shape.ruin()) as u8
+            ),
+        ];
+        lines.extend(options.iter().enumerate().map(|(idx, shape)| {
+            format!(
+                "option {} {} {} {}",
+                idx,
+                shape.cultivation().symbol(),
+                shape.has_coin() as u8,
+                single_line(&shape.geometry().to_string())
+            )
+        }));
+        lines.push("go".to_string());
+        Ok(lines)
+    }
+
+    fn send_season_score(&mut self) -> Result<(), &'static str> {
+        let season = *self.game.season().season_type();
+        let score = self
+            .game
+            .objectives()
+            .season_score(&season)
+            .ok_or("The season has not been scored")?;
+        let line = format!(
+            "scored {:?} {} {} {} {} {}",
+            season,
+            score.a.1.points(),
+            score.b.1.points(),
+            score.coin_count,
+            score.monster_penalty,
+            score.total()
+        );
+        self.bot.send(&line)
+    }
+}
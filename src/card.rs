@@ -1,16 +1,23 @@
+#[cfg(feature = "game")]
 use bevy::input::mouse::MouseButtonInput;
 use derive_deref::*;
 use serde::Deserialize;
 
-use crate::asset_management::AssetID;
-use crate::card_pile::NewCard;
-use crate::grid::Grid;
-use crate::mouse::MousePosition;
+use crate::grid::{Cultivation, Grid};
 use crate::shape::{Geometry, Shape};
-use crate::util::{contains_point, min_f};
-use crate::{asset_management::AssetManager, grid::Cultivation};
-use crate::{GRID_OFFSET, GRID_SIZE, SPRITE_SIZE};
+#[cfg(feature = "game")]
+use crate::{
+    asset_management::{AssetID, AssetManager},
+    events::OptionChosen,
+    mouse::MousePosition,
+    skill::Skills,
+    util::{contains_point, min_f},
+    GRID_OFFSET, GRID_SIZE, SPRITE_SIZE,
+};
+#[cfg(feature = "game")]
 use bevy::prelude::*;
+use std::fmt;
+use std::str::FromStr;
 
 // TODO: remove pub, as it does not need to be visible beyond this module
 #[derive(Deserialize, Clone)]
@@ -24,9 +31,14 @@ pub enum Card {
 #[derive(Clone)]
 pub enum CardClickEvent {
     SpawnShape(Shape),
-    Ruin,
 }
 
+/// which option of the card a clickable sprite belongs to, the left one is the first
+#[derive(Deref, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CardOption(pub usize);
+
+/// Pending ruin modifier. Ruin cards are never played on their own, they mark the next explore card,
+/// whose shape then has to cover at least one ruin.
 #[derive(Default, Deref, Clone, Copy)]
 pub struct RuinIndicator {
     inner: bool,
@@ -49,7 +61,15 @@ impl From<bool> for RuinIndicator {
 }
 
 impl Card {
+    /// the most time a single card can take
+    pub const MAX_TIME: i32 = 2;
+
+    #[cfg(feature = "game")]
     pub fn spawn(self, com: &mut Commands, assets: &AssetManager, ruin: &RuinIndicator) {
+        // ruins never make it onto the table, `CardPile::draw` folds them into the next explore card
+        if self.is_ruin() {
+            return;
+        }
         let handle = assets.fetch("blank_card").unwrap(); // TODO MAKE ME SAFE AND SOUND
         let transform = Transform::from_xyz(
             GRID_SIZE as f32 * SPRITE_SIZE + GRID_OFFSET * 2. + 100.,
@@ -77,9 +97,18 @@ impl Card {
             .insert(GlobalTransform::default())
             .id();
         //====== TIMER EN =====
+        let mut children = vec![font_entity];
+        if **ruin {
+            children.push(spawn_ruin_badge(
+                com,
+                assets,
+                Transform::from_xyz(100., 175., 0.1),
+                50.,
+            ));
+        }
         let entity = com
             .spawn()
-            .push_children(&[font_entity])
+            .push_children(&children)
             .insert_bundle(SpriteBundle {
                 material: handle,
                 transform,
@@ -87,11 +116,12 @@ impl Card {
             })
             .id();
 
+        let options = self.options(ruin);
         match &self {
-            Card::Shape(def) => def.spawn(com, entity, assets, ruin),
-            Card::Cultivation(def) => def.spawn(com, entity, assets, ruin),
-            Card::Splinter(def) => def.spawn(com, entity, assets, ruin),
-            Card::Ruin(def) => def.spawn(com, entity, assets),
+            Card::Shape(def) => def.spawn(com, entity, assets, options),
+            Card::Cultivation(def) => def.spawn(com, entity, assets, options),
+            Card::Splinter(def) => def.spawn(com, entity, assets, options),
+            Card::Ruin(_) => unreachable!("ruins are folded by CardPile::draw"),
         }
         com.entity(entity).insert(self);
     }
@@ -105,6 +135,35 @@ impl Card {
         }
     }
 
+    /// the shapes to choose from, the spawned card offers them in this order
+    pub fn options(&self, ruin: &RuinIndicator) -> Vec<Shape> {
+        match &self {
+            Self::Ruin(_) => Vec::default(),
+            Self::Splinter(_) => [
+                Cultivation::Farm,
+                Cultivation::Goblin,
+                Cultivation::Water,
+                Cultivation::Village,
+                Cultivation::Forest,
+            ]
+            .iter()
+            .map(|cultivation| Shape::new(&Geometry::default(), cultivation, ruin, false))
+            .collect(),
+            Self::Shape(def) => vec![
+                Shape::new(&def.left, &def.cultivation, ruin, true),
+                Shape::new(&def.right, &def.cultivation, ruin, false),
+            ],
+            Self::Cultivation(def) => vec![
+                Shape::new(&def.geometry, &def.left, ruin, false),
+                Shape::new(&def.geometry, &def.right, ruin, false),
+            ],
+        }
+    }
+
+    pub fn is_ruin(&self) -> bool {
+        matches!(self, Self::Ruin(_))
+    }
+
     pub fn time(&self) -> i32 {
         match &self {
             Card::Cultivation(_) => 2,
@@ -120,28 +179,120 @@ impl Default for Card {
     }
 }
 
-#[derive(Deserialize, Clone)]
-pub struct RuinDefinition;
-impl RuinDefinition {
-    fn spawn(&self, com: &mut Commands, parent: Entity, assets: &AssetManager) {
-        // Ruins don't do anything, move on, as soon as anything is clicked
-        // Make it look nice tho
-        let handle = assets.fetch("ruin").unwrap(); //wrap "ruin" into some constant?
-        let ent = com
-            .spawn()
-            .insert_bundle(SpriteBundle {
-                sprite: Sprite::new(Vec2::new(100., 100.)),
-                material: handle,
-                transform: Transform::from_xyz(0., 0., 0.1),
-                ..Default::default()
+/// A header line with the kind of card and its cultivations, followed by the geometries,
+/// separated by empty lines:
+/// ```text
+/// shape f
+/// @#
+///
+/// #.
+/// @#
+/// ```
+/// Splinter and ruin cards are only the header, cultivation cards name the left and right cultivation.
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Card::Splinter(_) => write!(f, "splinter"),
+            Card::Ruin(_) => write!(f, "ruin"),
+            Card::Shape(def) => write!(
+                f,
+                "shape {}\n{}\n\n{}",
+                def.cultivation.symbol(),
+                def.left,
+                def.right
+            ),
+            Card::Cultivation(def) => write!(
+                f,
+                "cultivation {} {}\n{}",
+                def.left.symbol(),
+                def.right.symbol(),
+                def.geometry
+            ),
+        }
+    }
+}
+
+impl FromStr for Card {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (header, rest) = s.split_once('\n').unwrap_or((s, ""));
+        let mut words = header.split_whitespace();
+        let kind = words.next().ok_or("A card starts with its kind")?;
+        let cultivations = words
+            .map(|word| {
+                let mut symbols = word.chars();
+                let cultivation = match (symbols.next(), symbols.next()) {
+                    (Some(symbol), None) => Cultivation::from_symbol(symbol),
+                    _ => None,
+                };
+                cultivation.ok_or("Unknown cultivation on card")
             })
-            .id();
-        com.entity(parent)
-            .push_children(&[ent])
-            .insert(CardClickEvent::Ruin);
+            .collect::<Result<Vec<_>, _>>()?;
+        // empty lines separate the geometries
+        let mut geometries = Vec::default();
+        let mut current = String::default();
+        for line in rest.lines().map(str::trim) {
+            if line.is_empty() {
+                if !current.is_empty() {
+                    geometries.push(current.parse::<Geometry>()?);
+                    current.clear();
+                }
+            } else {
+                current.push_str(line);
+                current.push('\n');
+            }
+        }
+        if !current.is_empty() {
+            geometries.push(current.parse()?);
+        }
+
+        match (kind, cultivations.as_slice(), geometries.as_slice()) {
+            ("splinter", [], []) => Ok(Card::Splinter(SplinterDefinition)),
+            ("ruin", [], []) => Ok(Card::Ruin(RuinDefinition)),
+            ("shape", [cultivation], [left, right]) => Ok(Card::Shape(ShapeDefinition {
+                left: left.clone(),
+                right: right.clone(),
+                cultivation: *cultivation,
+            })),
+            ("cultivation", [left, right], [geometry]) => {
+                Ok(Card::Cultivation(CultivationDefinition {
+                    geometry: geometry.clone(),
+                    left: *left,
+                    right: *right,
+                }))
+            }
+            ("splinter", ..) | ("ruin", ..) | ("shape", ..) | ("cultivation", ..) => {
+                Err("Wrong number of cultivations or geometries for this card")
+            }
+            _ => Err("Unknown kind of card"),
+        }
     }
 }
 
+#[derive(Deserialize, Clone)]
+pub struct RuinDefinition;
+
+#[cfg(feature = "game")]
+/// the "must be placed on a ruin" marker, used on the card as well as on the shape
+pub fn spawn_ruin_badge(
+    com: &mut Commands,
+    assets: &AssetManager,
+    transform: Transform,
+    size: f32,
+) -> Entity {
+    let handle = assets.fetch("ruin").unwrap(); //wrap "ruin" into some constant?
+    com.spawn()
+        .insert_bundle(SpriteBundle {
+            sprite: Sprite::new(Vec2::new(size, size)),
+            material: handle,
+            transform,
+            ..Default::default()
+        })
+        .id()
+}
+
 #[derive(Deserialize, Clone)]
 pub struct ShapeDefinition {
     left: Geometry,
@@ -150,12 +301,13 @@ pub struct ShapeDefinition {
 }
 
 impl ShapeDefinition {
+    #[cfg(feature = "game")]
     fn spawn(
         &self,
         com: &mut Commands,
         parent: Entity,
         assets: &AssetManager,
-        ruin: &RuinIndicator,
+        options: Vec<Shape>,
     ) {
         let transform = Transform::from_xyz(0., 75., 0.1); // TODO REMOVE MAGIC NUMBERS
         let handle = assets.fetch(self.cultivation.asset_id()).unwrap();
@@ -180,77 +332,55 @@ impl ShapeDefinition {
         );
 
         let normal_handle = assets.fetch("default").unwrap();
-
-        // all coins are on the left choice of shape cards
-        let left_spawner =
-            CardClickEvent::SpawnShape(Shape::new(&self.left, &self.cultivation, ruin, true));
-        let mut left_children: Vec<Entity> = self
-            .left
-            .as_transforms_centered(max_size, 0.2)
-            .iter()
-            .map(|&transform| {
-                com.spawn()
-                    .insert_bundle(SpriteBundle {
-                        sprite: Sprite::new(Vec2::new(max_size, max_size)),
-                        material: normal_handle.clone(),
-                        transform,
-                        ..Default::default()
-                    })
-                    .insert(left_spawner.clone()) // If I want an 'AREA' i can add this to the parent entity `left` and add an Rectangle, where it should be clicked...
-                    .id()
-            })
-            .collect();
-        // TODO: depending on how large the shape is, one should adapt this transform
-        let left_transform = Transform::from_xyz(-area.x / 2. - 10., -area.y / 2. - 10., 0.);
-
         let coin_handle = assets.fetch("coin").unwrap();
         let coin_transform = Transform::from_xyz(0., -100., 0.1);
-        //left coin
-        left_children.push(
-            com.spawn()
-                .insert_bundle(SpriteBundle {
-                    sprite: Sprite::new(Vec2::new(50., 50.)),
-                    material: coin_handle,
-                    transform: coin_transform,
-                    ..Default::default()
-                })
-                .id(),
-        );
 
-        let left = com
-            .spawn()
-            .insert(left_transform)
-            .insert(GlobalTransform::default())
-            .push_children(&left_children)
-            .id();
-        children.push(left);
+        // the left option is 0, the right one 1
+        for (idx, (shape, side)) in options.into_iter().zip([-1., 1.]).enumerate() {
+            let spawner = CardClickEvent::SpawnShape(shape.clone());
+            let mut side_children: Vec<Entity> = shape
+                .geometry()
+                .as_transforms_centered(max_size, 0.2)
+                .iter()
+                .map(|&transform| {
+                    com.spawn()
+                        .insert_bundle(SpriteBundle {
+                            sprite: Sprite::new(Vec2::new(max_size, max_size)),
+                            material: normal_handle.clone(),
+                            transform,
+                            ..Default::default()
+                        })
+                        .insert(spawner.clone())
+                        .insert(CardOption(idx)) // If I want an 'AREA' i can add this to the parent entity `left` and add an Rectangle, where it should be clicked...
+                        .id()
+                })
+                .collect();
+
+            // all coins are on the left choice of shape cards
+            if shape.has_coin() {
+                side_children.push(
+                    com.spawn()
+                        .insert_bundle(SpriteBundle {
+                            sprite: Sprite::new(Vec2::new(50., 50.)),
+                            material: coin_handle.clone(),
+                            transform: coin_transform,
+                            ..Default::default()
+                        })
+                        .id(),
+                );
+            }
 
-        let right_spawner =
-            CardClickEvent::SpawnShape(Shape::new(&self.right, &self.cultivation, ruin, false));
-        let right_children: Vec<Entity> = self
-            .right
-            .as_transforms_centered(max_size, 0.2)
-            .iter()
-            .map(|&transform| {
-                com.spawn()
-                    .insert_bundle(SpriteBundle {
-                        sprite: Sprite::new(Vec2::new(max_size, max_size)),
-                        material: normal_handle.clone(),
-                        transform,
-                        ..Default::default()
-                    })
-                    .insert(right_spawner.clone())
-                    .id()
-            })
-            .collect();
-        let right_transform = Transform::from_xyz(area.x / 2. + 10., -area.y / 2. - 10., 0.);
-        let right = com
-            .spawn()
-            .insert(right_transform)
-            .insert(GlobalTransform::default())
-            .push_children(&right_children)
-            .id();
-        children.push(right);
+            // TODO: depending on how large the shape is, one should adapt this transform
+            let side_transform =
+                Transform::from_xyz(side * (area.x / 2. + 10.), -area.y / 2. - 10., 0.);
+            let side_entity = com
+                .spawn()
+                .insert(side_transform)
+                .insert(GlobalTransform::default())
+                .push_children(&side_children)
+                .id();
+            children.push(side_entity);
+        }
 
         com.entity(parent).push_children(&children);
     }
@@ -267,12 +397,13 @@ pub struct CultivationDefinition {
 }
 
 impl CultivationDefinition {
+    #[cfg(feature = "game")]
     pub fn spawn(
         &self,
         com: &mut Commands,
         parent: Entity,
         assets: &AssetManager,
-        ruin: &RuinIndicator,
+        options: Vec<Shape>,
     ) {
         let top_offset = Vec3::new(0., 75., 0.1);
         let top_window = Vec2::new(200., 125.); //TODO REMOVE MAGIC numbers
@@ -298,38 +429,22 @@ impl CultivationDefinition {
             })
             .collect();
 
-        // Cultivation children
-        let left_transform = Transform::from_xyz(-50., -50., 0.1);
-        let left_spawn =
-            CardClickEvent::SpawnShape(Shape::new(&self.geometry, &self.left, ruin, false));
-        let left_mat = assets.fetch(self.left.asset_id()).unwrap();
-        children.push(
-            com.spawn()
-                .insert_bundle(SpriteBundle {
-                    sprite: Sprite::new(Vec2::new(cultivation_size, cultivation_size)),
-                    material: left_mat,
-                    transform: left_transform,
-                    ..Default::default()
-                })
-                .insert(left_spawn)
-                .id(),
-        );
-
-        let right_transform = Transform::from_xyz(50., -50., 0.1);
-        let right_spawn =
-            CardClickEvent::SpawnShape(Shape::new(&self.geometry, &self.right, ruin, false));
-        let right_mat = assets.fetch(self.right.asset_id()).unwrap();
-        children.push(
-            com.spawn()
-                .insert_bundle(SpriteBundle {
-                    sprite: Sprite::new(Vec2::new(cultivation_size, cultivation_size)),
-                    material: right_mat,
-                    transform: right_transform,
-                    ..Default::default()
-                })
-                .insert(right_spawn)
-                .id(),
-        );
+        // Cultivation children, the left one is option 0
+        for (idx, (shape, x)) in options.into_iter().zip([-50., 50.]).enumerate() {
+            let material = assets.fetch(shape.cultivation().asset_id()).unwrap();
+            children.push(
+                com.spawn()
+                    .insert_bundle(SpriteBundle {
+                        sprite: Sprite::new(Vec2::new(cultivation_size, cultivation_size)),
+                        material,
+                        transform: Transform::from_xyz(x, -50., 0.1),
+                        ..Default::default()
+                    })
+                    .insert(CardClickEvent::SpawnShape(shape))
+                    .insert(CardOption(idx))
+                    .id(),
+            );
+        }
         com.entity(parent).push_children(&children);
     }
 
@@ -340,52 +455,39 @@ impl CultivationDefinition {
 #[derive(Deserialize, Clone)]
 pub struct SplinterDefinition;
 impl SplinterDefinition {
+    #[cfg(feature = "game")]
     pub fn spawn(
         &self,
         com: &mut Commands,
         parent: Entity,
         assets: &AssetManager,
-        ruin: &RuinIndicator,
+        options: Vec<Shape>,
     ) {
         // we just have a 5 choice Cultivation card with a geometry of [(0,0)]
-        let geom = Geometry::default();
-
         // TODO: remove magic numbers
         const SPLINTER_OFFSET: f32 = 75.;
-        let shapes = vec![
-            (
-                Shape::new(&geom, &Cultivation::Farm, ruin, false),
-                Transform::from_xyz(SPLINTER_OFFSET, SPLINTER_OFFSET, 0.1),
-            ),
-            (
-                Shape::new(&geom, &Cultivation::Goblin, ruin, false),
-                Transform::from_xyz(SPLINTER_OFFSET, -SPLINTER_OFFSET, 0.1),
-            ),
-            (
-                Shape::new(&geom, &Cultivation::Water, ruin, false),
-                Transform::from_xyz(-SPLINTER_OFFSET, SPLINTER_OFFSET, 0.1),
-            ),
-            (
-                Shape::new(&geom, &Cultivation::Village, ruin, false),
-                Transform::from_xyz(-SPLINTER_OFFSET, -SPLINTER_OFFSET, 0.1),
-            ),
-            (
-                Shape::new(&geom, &Cultivation::Forest, ruin, false),
-                Transform::from_xyz(0., 0., 0.1),
-            ),
+        let transforms = [
+            Transform::from_xyz(SPLINTER_OFFSET, SPLINTER_OFFSET, 0.1),
+            Transform::from_xyz(SPLINTER_OFFSET, -SPLINTER_OFFSET, 0.1),
+            Transform::from_xyz(-SPLINTER_OFFSET, SPLINTER_OFFSET, 0.1),
+            Transform::from_xyz(-SPLINTER_OFFSET, -SPLINTER_OFFSET, 0.1),
+            Transform::from_xyz(0., 0., 0.1),
         ];
-        let children: Vec<Entity> = shapes
-            .iter()
-            .map(|(shape, transform)| {
+        let children: Vec<Entity> = options
+            .into_iter()
+            .zip(transforms)
+            .enumerate()
+            .map(|(idx, (shape, transform))| {
                 let material = assets.fetch(shape.cultivation().asset_id()).unwrap();
                 com.spawn()
                     .insert_bundle(SpriteBundle {
                         sprite: Sprite::new(Vec2::new(50., 50.)),
                         material,
-                        transform: *transform,
+                        transform,
                         ..Default::default()
                     })
-                    .insert(CardClickEvent::SpawnShape(shape.clone()))
+                    .insert(CardClickEvent::SpawnShape(shape))
+                    .insert(CardOption(idx))
                     .id()
             })
             .collect();
@@ -397,20 +499,20 @@ impl SplinterDefinition {
     }
 }
 
-#[allow(clippy::too_many_arguments)]
+#[cfg(feature = "game")]
 pub fn click_card(
     mut com: Commands,
-    query: Query<(&CardClickEvent, &GlobalTransform, &Sprite, Entity)>,
+    query: Query<(&CardClickEvent, &CardOption, &GlobalTransform, &Sprite)>,
     shape: Query<(&Shape, Entity)>,
     mut events: EventReader<MouseButtonInput>,
-    mut ruin: ResMut<RuinIndicator>,
     position: Res<MousePosition>,
     assets: Res<AssetManager>,
-    mut next_card: EventWriter<NewCard>,
+    skills: Res<Skills>,
+    mut chosen: EventWriter<OptionChosen>,
 ) {
     for event in events.iter() {
         if event.button == MouseButton::Left && event.state.is_pressed() {
-            for (shape_spawner, transform, sprite, entity) in query.iter() {
+            for (shape_spawner, option, transform, sprite) in query.iter() {
                 if contains_point(
                     &transform.translation.truncate(),
                     &sprite.size,
@@ -423,12 +525,15 @@ pub fn click_card(
                     //let s = shape_spawner.shape.clone();
                     match &shape_spawner {
                         CardClickEvent::SpawnShape(shape) => {
-                            shape.clone().spawn(&mut com, &assets);
-                        }
-                        CardClickEvent::Ruin => {
-                            com.entity(entity).despawn_recursive();
-                            ruin.set();
-                            next_card.send(NewCard)
+                            let mut shape = shape.clone();
+                            if skills.ignores_ruin() {
+                                shape.ignore_ruin();
+                            }
+                            chosen.send(OptionChosen {
+                                option: **option,
+                                shape: shape.clone(),
+                            });
+                            shape.spawn(&mut com, &assets);
                         }
                     };
                     return;
@@ -1,34 +1,74 @@
+#[cfg(feature = "game")]
+use crate::{
+    asset_management::AssetManager, config::GameRng, events::CardRevealed, players::Players,
+    seasons::Season, GameState,
+};
 use crate::{
-    asset_management::AssetManager,
     card::{Card, RuinIndicator},
     grid::Grid,
-    seasons::Season,
-    GameState,
 };
+#[cfg(feature = "game")]
 use bevy::{
     asset::{AssetLoader, LoadContext, LoadedAsset},
     prelude::*,
     reflect::TypeUuid,
     utils::BoxedFuture,
 };
-use rand::{prelude::SliceRandom, thread_rng};
+use bevy_utils::tracing as log;
+#[cfg(feature = "game")]
+use rand::{prelude::SliceRandom, Rng};
 use serde::Deserialize;
+use std::str::FromStr;
 
-#[derive(Deserialize, TypeUuid, Clone)]
-#[uuid = "60f975dc-d667-11eb-b8bc-0242ac130003"]
+#[derive(Deserialize, Clone)]
+#[cfg_attr(
+    feature = "game",
+    derive(TypeUuid),
+    uuid = "60f975dc-d667-11eb-b8bc-0242ac130003"
+)]
 pub struct CardPile {
     pub cards: Vec<Card>,
 }
 
 impl CardPile {
-    fn shuffle(&mut self) {
-        self.cards.shuffle(&mut thread_rng())
+    #[cfg(feature = "game")]
+    fn shuffle(&mut self, rng: &mut impl Rng) {
+        self.cards.shuffle(rng)
+    }
+
+    /// Draws the next explore card.
+    /// Ruin cards on top of it are not played on their own, they only require the drawn card to be placed on a ruin.
+    /// This way several ruins in a row collapse into a single requirement.
+    pub fn draw(&mut self, ruin: &mut RuinIndicator) -> Option<Card> {
+        while let Some(card) = self.cards.pop() {
+            if card.is_ruin() {
+                ruin.set();
+            } else {
+                return Some(card);
+            }
+        }
+        // there is no card left, that a ruin at the bottom of the pile could mark
+        ruin.reset();
+        None
     }
 }
 
+/// Swaps a drawn card, that cannot be placed, for the default splinter card and returns the swapped card.
+/// The splinter card does not have to be placed on a ruin.
+pub fn replace_unplacable(card: &mut Card, grid: &Grid, ruin: &mut RuinIndicator) -> Option<Card> {
+    if card.is_placable(grid, ruin) {
+        return None;
+    }
+    log::debug!("Card cannot be placed, fallback to default splinter card");
+    ruin.reset();
+    Some(std::mem::take(card))
+}
+
+#[cfg(feature = "game")]
 #[derive(Default)]
 pub struct CardPileLoader;
 
+#[cfg(feature = "game")]
 impl AssetLoader for CardPileLoader {
     fn load<'a>(
         &'a self,
@@ -47,6 +87,15 @@ impl AssetLoader for CardPileLoader {
     }
 }
 
+/// the content of a `.cardpile` file
+impl FromStr for CardPile {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ron::de::from_str(s).map_err(|_| "Not a valid card pile")
+    }
+}
+
 impl Default for CardPile {
     fn default() -> Self {
         CardPile {
@@ -57,48 +106,100 @@ impl Default for CardPile {
 
 pub struct NewCard;
 
+#[cfg(feature = "game")]
+/// Draws the next card for the first player, the players after them get the same card on their own maps.
 #[allow(clippy::too_many_arguments)]
 pub fn next_card(
     mut com: Commands,
     mut reader: EventReader<NewCard>,
     grid: Res<Grid>,
+    players: Res<Players>,
     mut current_season: ResMut<Season>,
     mut pile: ResMut<CardPile>,
     mut ruin: ResMut<RuinIndicator>,
     mut state: ResMut<State<GameState>>,
     assets: Res<AssetManager>,
+    mut revealed: EventWriter<CardRevealed>,
+    // the card drawn for the first player and its ruin, before it was replaced
+    mut drawn: Local<Option<(Card, RuinIndicator)>>,
 ) {
     // we don't care how often, just that someone wants to spawn a new card...
     if reader.iter().count() > 0 {
-        if !current_season.has_time_left() {
-            //trigger season end stuffy buffy flingy bingy
-            state.push(GameState::SeasonScoreState).unwrap();
-            return;
-        }
-        if let Some(mut card) = pile.cards.pop() {
-            // time is added before cards are placed
-            current_season.pass_time(card.time());
-            // test whether you can play this card
-            if !card.is_placable(&grid, &ruin) {
-                println!("Card cannot be placed, fallback to default splinter card");
-                card = Card::default();
-                ruin.reset(); // if card is replaced, it does not need to be placed on ruins
+        let card = if players.current() == 0 {
+            if !current_season.has_time_left() {
+                // the pile is shuffled again for the next season, a pending ruin does not carry over
+                ruin.reset();
+                //trigger season end stuffy buffy flingy bingy
+                state.push(GameState::SeasonScoreState).unwrap();
+                return;
             }
+            let card = pile.draw(&mut ruin);
+            if let Some(card) = &card {
+                // time is added before cards are placed
+                current_season.pass_time(card.time());
+                *drawn = Some((card.clone(), *ruin));
+            }
+            card
+        } else {
+            drawn.clone().map(|(card, drawn_ruin)| {
+                *ruin = drawn_ruin;
+                card
+            })
+        };
+
+        if let Some(mut card) = card {
+            // the replacement depends on the map of the player on turn
+            let replaced = replace_unplacable(&mut card, &grid, &mut ruin);
 
+            revealed.send(CardRevealed {
+                card: card.clone(),
+                season: *current_season.season_type(),
+                passed_time: current_season.passed_time(),
+                ruin: **ruin,
+                replaced,
+                player: players.current(),
+            });
             card.spawn(&mut com, &assets, &ruin);
+            // the shapes of the card carry the ruin requirement from now on
             ruin.reset();
         }
     }
 }
 
+/// Cards already played in a position loaded from a `BoardCode`.
+/// They are drawn without being revealed, so the game continues with the card that followed them.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TurnsToSkip(pub usize);
+
+#[cfg(feature = "game")]
 pub fn initialize_cards(
     mut com: Commands,
     assets: Res<AssetManager>,
-    mut storage: ResMut<Assets<CardPile>>,
+    storage: Res<Assets<CardPile>>,
     mut next: EventWriter<NewCard>,
+    mut rng: ResMut<GameRng>,
+    mut season: ResMut<Season>,
+    mut skipped: ResMut<TurnsToSkip>,
 ) {
-    let mut cards = storage.get_mut(&assets.cards).unwrap().clone();
-    cards.shuffle();
+    let pile = storage.get(&assets.cards).unwrap();
+    let mut cards = pile.clone();
+    cards.shuffle(&mut **rng);
+
+    // the pile is drawn like in the game itself, the seasons before are not scored
+    while skipped.0 > 0 {
+        if season.has_time_left() {
+            if let Some(card) = cards.draw(&mut RuinIndicator::default()) {
+                season.pass_time(card.time());
+            }
+            skipped.0 -= 1;
+        } else if let Some(next_season) = season.next() {
+            *season = next_season;
+            cards = pile.clone();
+            cards.shuffle(&mut **rng);
+        } else {
+            skipped.0 = 0;
+        }
+    }
 
     // will override old CardPile if existent
     com.insert_resource(cards);
@@ -0,0 +1,108 @@
+use derive_deref::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::board_code::BoardCode;
+use crate::grid::Coordinate;
+
+/// positions of the special terrain, everything else is normal land
+#[derive(Debug, Clone)]
+pub struct MapConfig {
+    pub mountains: Vec<Coordinate>,
+    pub ruins: Vec<Coordinate>,
+}
+
+impl Default for MapConfig {
+    fn default() -> Self {
+        Self {
+            //(x,y)
+            mountains: vec![
+                (2, 2).into(),
+                (3, 9).into(),
+                (5, 5).into(),
+                (7, 1).into(),
+                (8, 8).into(),
+            ],
+            ruins: vec![
+                (1, 2).into(),
+                (1, 8).into(),
+                (5, 1).into(),
+                (5, 9).into(),
+                (9, 2).into(),
+                (9, 8).into(),
+            ],
+        }
+    }
+}
+
+/// Inserted as resource by the `KarthographPlugin`.
+#[derive(Debug, Clone)]
+pub struct GameConfig {
+    pub map: MapConfig,
+    /// relative to the asset folder
+    pub card_pile: String,
+    /// `None` draws a new game every start, the `KarthographPlugin` fills in the drawn seed
+    pub seed: Option<u64>,
+    /// All players share the card pile, the objectives and the skills, so they play the same game on their own maps.
+    /// They place every card one after another, the bundled UI shows the map of the player on turn.
+    pub players: usize,
+    /// where the map is written as SVG at the end of the game
+    pub svg: Option<String>,
+    /// where the summary of the game is written as JSON at its end
+    pub summary: Option<String>,
+    /// a `BoardCode` to start from instead of the empty map, its seed replaces `seed`
+    pub position: Option<String>,
+}
+
+impl GameConfig {
+    pub const USAGE: &'static str =
+        "[--seed N] [--players N] [--code BOARD_CODE] [--svg PATH] [--summary PATH]";
+
+    /// the default config with the options of the command line, see `USAGE`
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, &'static str> {
+        let mut config = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let value = args.next().ok_or("Every option needs a value")?;
+            match arg.as_str() {
+                "--seed" => config.seed = Some(value.parse().map_err(|_| "The seed is a number")?),
+                "--players" => match value.parse() {
+                    Ok(players) if players > 0 => config.players = players,
+                    _ => return Err("The number of players is a number above 0"),
+                },
+                "--svg" => config.svg = Some(value),
+                "--summary" => config.summary = Some(value),
+                "--code" => {
+                    value.parse::<BoardCode>()?;
+                    config.position = Some(value);
+                }
+                _ => return Err("Unknown option"),
+            }
+        }
+        Ok(config)
+    }
+
+    pub fn rng(&self) -> StdRng {
+        match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        }
+    }
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            map: MapConfig::default(),
+            card_pile: "content.cardpile".to_string(),
+            seed: None,
+            players: 1,
+            svg: None,
+            summary: None,
+            position: None,
+        }
+    }
+}
+
+/// Every random decision of the game is drawn from here, so a seed reproduces the whole game
+#[derive(Deref, DerefMut)]
+pub struct GameRng(pub StdRng);
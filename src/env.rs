@@ -0,0 +1,275 @@
+use crate::bot::{play_move, Move};
+use crate::card::{Card, RuinIndicator};
+use crate::config::GameConfig;
+use crate::grid::{Cultivation, Grid, Placement, Terrain};
+use crate::headless::{HeadlessGame, Step};
+use crate::objective::all_objectives;
+use crate::seasons::SeasonType;
+use crate::shape::{Orientation, Shape};
+use crate::GameState;
+
+const FIELDS: usize = Grid::SIZE * Grid::SIZE;
+const CULTIVATIONS: [Cultivation; 5] = [
+    Cultivation::Village,
+    Cultivation::Water,
+    Cultivation::Farm,
+    Cultivation::Forest,
+    Cultivation::Goblin,
+];
+// no cultivation and the five cultivations, then normal land, ruin, mountain with coin and without
+const FIELD_FEATURES: usize = 6 + 4;
+// the geometries of the options have to fit into this square, see `Env::reset`
+const GEOMETRY_SIZE: usize = 5;
+// present, the cultivation, coin and the fields of the geometry
+const OPTION_FEATURES: usize = 1 + 5 + 1 + GEOMETRY_SIZE * GEOMETRY_SIZE;
+// time and ruin of the card
+const CARD_FEATURES: usize = 2;
+// the season, its passed and total time
+const SEASON_FEATURES: usize = 4 + 2;
+const OBJECTIVE_COUNT: usize = 16;
+// coins are divided by it, more are seen as this many
+const MAX_COINS: f32 = 14.;
+
+/// One step of the `Env`
+#[derive(Debug, Clone)]
+pub struct Transition {
+    pub observation: Vec<f32>,
+    /// points gained with this step, the seasons are scored after their last card
+    pub reward: f32,
+    pub done: bool,
+}
+
+/// A reinforcement learning environment in the style of OpenAI Gym.
+///
+/// An action is a placement of an option of the card: `(option * 8 + orientation) * 121 + anchor`, where
+/// `orientation` indexes `Orientation::ALL` and `anchor` is `x + 11 * y`. The mask tells which of them are legal,
+/// built from `Grid::legal_placements`, so symmetric shapes allow each placement with one orientation only.
+/// Scoring screens are skipped, skills are not part of the actions.
+///
+/// The observation has a fixed size, all values are between 0 and 1:
+/// - per field, row by row from the bottom: one-hot cultivation (none first) and one-hot terrain
+/// - per option of the card: whether it exists, one-hot cultivation, coin and its normalized geometry as 5x5 square
+/// - time and ruin of the card
+/// - one-hot season, its passed and total time, both divided by the longest season and card
+/// - per objective A to D: one-hot id, in the order of `all_objectives`
+/// - coins
+pub struct Env {
+    pub config: GameConfig,
+    game: Option<HeadlessGame>,
+}
+
+impl Env {
+    /// splinter cards offer a single field of each cultivation, all other cards two shapes
+    pub const MAX_OPTIONS: usize = 5;
+    pub const ACTION_COUNT: usize = Self::MAX_OPTIONS * Orientation::ALL.len() * FIELDS;
+    pub const OBSERVATION_SIZE: usize = FIELDS * FIELD_FEATURES
+        + Self::MAX_OPTIONS * OPTION_FEATURES
+        + CARD_FEATURES
+        + SEASON_FEATURES
+        + 4 * OBJECTIVE_COUNT
+        + 1;
+
+    /// the seed of the config is replaced on every `reset`
+    pub fn new(config: GameConfig) -> Self {
+        Self { config, game: None }
+    }
+
+    /// starts a new game, fails for card piles with shapes too large for the observation
+    pub fn reset(&mut self, seed: u64) -> Result<Vec<f32>, &'static str> {
+        // rewards and observations follow a single map
+        if self.config.players != 1 {
+            return Err("The environment is played by a single player");
+        }
+        let mut game = HeadlessGame::new(GameConfig {
+            seed: Some(seed),
+            ..self.config.clone()
+        });
+        game.load()?;
+        let fits = game
+            .card_pile()
+            .cards
+            .iter()
+            .flat_map(|card| card.options(&RuinIndicator::from(true)))
+            .all(|shape| {
+                let (width, height) = shape.geometry().extent();
+                width <= GEOMETRY_SIZE && height <= GEOMETRY_SIZE
+            });
+        if !fits {
+            return Err("A shape of the card pile is too large for the observation");
+        }
+        self.game = Some(game);
+        Ok(self.observation())
+    }
+
+    /// places the shape as the action says, actions not allowed by the mask change nothing and fail
+    pub fn step(&mut self, action: usize) -> Result<Transition, &'static str> {
+        let game = self
+            .game
+            .as_mut()
+            .ok_or("The environment has to be reset first")?;
+        if game.state() != GameState::SeasonState {
+            return Err("The game is over");
+        }
+        let before = game.objectives().total_score();
+
+        let chosen = Self::decode(action)?;
+        let options = game.options();
+        let shape = options
+            .get(chosen.option)
+            .ok_or("The card has no such option")?;
+        let distinct = shape
+            .geometry()
+            .orientations()
+            .iter()
+            .any(|(orientation, _)| *orientation == chosen.placement.orientation);
+        if !distinct {
+            return Err("The orientation repeats another one of the shape");
+        }
+        play_move(game, &chosen)?;
+        while game.state() == GameState::SeasonScoreState {
+            game.play(vec![Step::Continue])?;
+        }
+
+        let reward = (game.objectives().total_score() - before) as f32;
+        let done = game.state() == GameState::End;
+        Ok(Transition {
+            observation: self.observation(),
+            reward,
+            done,
+        })
+    }
+
+    pub fn encode(chosen: &Move) -> usize {
+        let orientation = Orientation::ALL
+            .iter()
+            .position(|orientation| *orientation == chosen.placement.orientation)
+            .expect("every orientation is one of all");
+        let anchor =
+            chosen.placement.anchor.x as usize + Grid::SIZE * chosen.placement.anchor.y as usize;
+        (chosen.option * Orientation::ALL.len() + orientation) * FIELDS + anchor
+    }
+
+    pub fn decode(action: usize) -> Result<Move, &'static str> {
+        if action >= Self::ACTION_COUNT {
+            return Err("There is no such action");
+        }
+        let anchor = action % FIELDS;
+        let orientation = Orientation::ALL[action / FIELDS % Orientation::ALL.len()];
+        Ok(Move {
+            option: action / FIELDS / Orientation::ALL.len(),
+            placement: Placement {
+                orientation,
+                anchor: (anchor % Grid::SIZE, anchor / Grid::SIZE).into(),
+            },
+        })
+    }
+
+    /// the legal actions, all of them are false once the game is over
+    pub fn action_mask(&mut self) -> Vec<bool> {
+        let mut mask = vec![false; Self::ACTION_COUNT];
+        let game = match self.game.as_mut() {
+            Some(game) if game.state() == GameState::SeasonState => game,
+            _ => return mask,
+        };
+        for (option, shape) in game.options().iter().enumerate() {
+            for placement in game
+                .grid()
+                .legal_placements(shape.geometry(), &shape.ruin())
+            {
+                mask[Self::encode(&Move { option, placement })] = true;
+            }
+        }
+        mask
+    }
+
+    pub fn observation(&mut self) -> Vec<f32> {
+        let mut observation = Vec::with_capacity(Self::OBSERVATION_SIZE);
+        let game = match self.game.as_mut() {
+            Some(game) => game,
+            None => return vec![0.; Self::OBSERVATION_SIZE],
+        };
+
+        for field in game.grid().all() {
+            let cultivation = field.cultivation.as_ref().map(|info| *info.cultivation());
+            observation.push(cultivation.is_none() as u8 as f32);
+            observation.extend(
+                CULTIVATIONS
+                    .iter()
+                    .map(|other| (cultivation == Some(*other)) as u8 as f32),
+            );
+            let terrain = field.terrain();
+            observation.extend(
+                [
+                    Terrain::Normal,
+                    Terrain::Ruin,
+                    Terrain::Mountain(true),
+                    Terrain::Mountain(false),
+                ]
+                .iter()
+                .map(|other| (terrain == *other) as u8 as f32),
+            );
+        }
+
+        let in_season = game.state() == GameState::SeasonState;
+        let options: Vec<Shape> = if in_season {
+            game.options()
+        } else {
+            Vec::default()
+        };
+        for idx in 0..Self::MAX_OPTIONS {
+            match options.get(idx) {
+                Some(shape) => {
+                    observation.push(1.);
+                    observation.extend(
+                        CULTIVATIONS
+                            .iter()
+                            .map(|other| (shape.cultivation() == *other) as u8 as f32),
+                    );
+                    observation.push(shape.has_coin() as u8 as f32);
+                    let geometry = shape.geometry().normalized();
+                    for y in 0..GEOMETRY_SIZE {
+                        for x in 0..GEOMETRY_SIZE {
+                            let pos = (x, y).into();
+                            observation.push(geometry.contains(&pos) as u8 as f32);
+                        }
+                    }
+                }
+                None => observation.extend([0.; OPTION_FEATURES]),
+            }
+        }
+        let card = if in_season { game.card() } else { None };
+        observation.push(card.map_or(0., |card| card.time() as f32 / Card::MAX_TIME as f32));
+        observation.push(
+            options
+                .first()
+                .map_or(0., |shape| *shape.ruin() as u8 as f32),
+        );
+
+        let season = game.season();
+        observation.extend(
+            SeasonType::ALL
+                .iter()
+                .map(|other| (season.season_type() == other) as u8 as f32),
+        );
+        // the last card of a season may take more time than is left
+        let max_time = (SeasonType::max_time() + Card::MAX_TIME) as f32;
+        observation.push(season.passed_time() as f32 / max_time);
+        observation.push(season.total_time() as f32 / max_time);
+
+        let ids: Vec<&str> = all_objectives()
+            .iter()
+            .map(|objective| objective.name())
+            .collect();
+        for objective in game.objectives().objectives() {
+            observation.extend(ids.iter().map(|id| (*id == objective.name()) as u8 as f32));
+        }
+        observation.push((game.objectives().coins() as f32 / MAX_COINS).min(1.));
+
+        debug_assert_eq!(observation.len(), Self::OBSERVATION_SIZE);
+        observation
+    }
+
+    pub fn game(&mut self) -> Option<&mut HeadlessGame> {
+        self.game.as_mut()
+    }
+}
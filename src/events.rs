@@ -0,0 +1,75 @@
+//! Events describing the course of a game.
+//! Together they are enough to replay it, so statistics, achievements or overlays can be built
+//! without touching the systems of the game itself. Changes of single fields are sent as `FieldChanged`.
+
+use crate::card::Card;
+use crate::grid::{Coordinate, Cultivation};
+use crate::objective::{CoinSource, SeasonScore};
+use crate::seasons::SeasonType;
+use crate::shape::Shape;
+use crate::skill::SkillEffect;
+
+/// A card was put on the table. Ruin cards are never revealed on their own, they set `ruin` instead.
+pub struct CardRevealed {
+    pub card: Card,
+    pub season: SeasonType,
+    /// time of the season passed, including this card
+    pub passed_time: i32,
+    pub ruin: bool,
+    /// the drawn card, if it could not be placed anywhere and got replaced by a splinter
+    pub replaced: Option<Card>,
+    /// the player on turn, every player places the same cards on their own map
+    pub player: usize,
+}
+
+/// An option of the card in play was picked, the shape is in hand now
+pub struct OptionChosen {
+    /// counted like `CardOption`, the left one is 0
+    pub option: usize,
+    pub shape: Shape,
+}
+
+/// A shape was put onto the grid
+#[derive(Debug, Clone)]
+pub struct ShapePlaced {
+    pub cultivation: Cultivation,
+    pub fields: Vec<Coordinate>,
+    /// the fields were cultivated before
+    pub recultivated: bool,
+    /// the shape was granted by a skill
+    pub bonus: bool,
+}
+
+/// A revealed skill was bought for the card in play, its cost is taken from the coins
+#[derive(Debug, Clone)]
+pub struct SkillBought {
+    /// the index of the skill among the revealed ones
+    pub index: usize,
+    pub cost: usize,
+    pub effect: SkillEffect,
+}
+
+/// The bought skill could not be used, its cost went back to the coins
+#[derive(Debug, Clone)]
+pub struct SkillRefunded {
+    pub index: usize,
+    pub cost: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct CoinGained {
+    pub source: CoinSource,
+    /// the fields of the shape or the mountain, the coin comes from
+    pub position: Vec<Coordinate>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SeasonScored {
+    pub season: SeasonType,
+    pub score: SeasonScore,
+}
+
+#[derive(Debug, Clone)]
+pub struct GameEnded {
+    pub total: isize,
+}
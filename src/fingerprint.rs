@@ -0,0 +1,123 @@
+use crate::grid::{Coordinate, Cultivation, Grid, Terrain};
+use crate::shape::Orientation;
+
+const FIELDS: usize = Grid::SIZE * Grid::SIZE;
+const TERRAINS: usize = 4;
+const CULTIVATIONS: usize = 5;
+
+// splitmix64, so the keys are the same on every machine and multiplayer clients can compare hashes
+const fn next_key(state: u64) -> u64 {
+    let mut z = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+const fn key_table<const N: usize>(seed: u64) -> [[u64; N]; FIELDS] {
+    let mut table = [[0; N]; FIELDS];
+    let mut state = seed;
+    let mut field = 0;
+    while field < FIELDS {
+        let mut kind = 0;
+        while kind < N {
+            state = next_key(state);
+            table[field][kind] = state;
+            kind += 1;
+        }
+        field += 1;
+    }
+    table
+}
+
+const TERRAIN_KEYS: [[u64; TERRAINS]; FIELDS] = key_table(0x4B61_7274_686F);
+const CULTIVATION_KEYS: [[u64; CULTIVATIONS]; FIELDS] = key_table(0x6772_6170_6800);
+
+fn terrain_index(terrain: Terrain) -> usize {
+    match terrain {
+        Terrain::Normal => 0,
+        Terrain::Mountain(true) => 1,
+        Terrain::Mountain(false) => 2,
+        Terrain::Ruin => 3,
+    }
+}
+
+fn field_index(coord: &Coordinate) -> usize {
+    coord.x as usize + coord.y as usize * Grid::SIZE
+}
+
+/// maps a field onto the field it ends up on, if the whole board is mirrored and rotated
+pub fn transform(orientation: &Orientation, coord: &Coordinate) -> Coordinate {
+    let last = Grid::SIZE as i32 - 1;
+    let (mut x, mut y) = (coord.x, coord.y);
+    if orientation.mirrored {
+        x = last - x;
+    }
+    for _ in 0..orientation.rotations {
+        let rotated = (y, last - x);
+        x = rotated.0;
+        y = rotated.1;
+    }
+    (x, y).into()
+}
+
+/// Zobrist hash of the board, updated with every change of a field.
+/// Next to the plain hash, one hash per symmetry of the map is kept, so positions that only differ
+/// by a symmetry of the map share the same `canonical` hash.
+#[derive(Debug, Clone)]
+pub struct Fingerprint {
+    // the identity is always the first one
+    symmetries: Vec<Orientation>,
+    hashes: Vec<u64>,
+}
+
+impl Fingerprint {
+    pub fn new(mut symmetries: Vec<Orientation>) -> Self {
+        symmetries.retain(|orientation| *orientation != Orientation::default());
+        symmetries.insert(0, Orientation::default());
+        let hashes = vec![0; symmetries.len()];
+        Self { symmetries, hashes }
+    }
+
+    /// the board transformations, under which the terrain of the map stays the same
+    pub fn map_symmetries(grid: &Grid) -> Vec<Orientation> {
+        Orientation::ALL
+            .iter()
+            .copied()
+            .filter(|orientation| {
+                grid.all().all(|field| {
+                    let other = grid.at(&transform(orientation, &field.position())).unwrap();
+                    other.terrain() == field.terrain()
+                })
+            })
+            .collect()
+    }
+
+    pub fn hash(&self) -> u64 {
+        self.hashes[0]
+    }
+
+    /// equal for positions, that can be transformed into each other by a symmetry of the map
+    pub fn canonical(&self) -> u64 {
+        *self.hashes.iter().min().unwrap()
+    }
+
+    pub fn symmetries(&self) -> &[Orientation] {
+        &self.symmetries
+    }
+
+    fn toggle(&mut self, coord: &Coordinate, key: impl Fn(usize) -> u64) {
+        for (orientation, hash) in self.symmetries.iter().zip(self.hashes.iter_mut()) {
+            *hash ^= key(field_index(&transform(orientation, coord)));
+        }
+    }
+
+    /// adds the terrain to the hash, or removes it if it has been added before
+    pub fn toggle_terrain(&mut self, coord: &Coordinate, terrain: Terrain) {
+        self.toggle(coord, |field| TERRAIN_KEYS[field][terrain_index(terrain)]);
+    }
+
+    /// adds the cultivation to the hash, or removes it if it has been added before
+    pub fn toggle_cultivation(&mut self, coord: &Coordinate, cultivation: Cultivation) {
+        self.toggle(coord, |field| CULTIVATION_KEYS[field][cultivation as usize]);
+    }
+}
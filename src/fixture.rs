@@ -0,0 +1,79 @@
+use std::str::FromStr;
+
+use crate::grid::Grid;
+use crate::objective::all_objectives;
+
+/// A board together with the expected scores of some objectives:
+/// ```text
+/// # lines starting with a hash are comments
+/// <the 11 rows of the board, as read by `Grid::from_str`>
+/// Metropole = 3
+/// ```
+pub struct Fixture {
+    pub grid: Grid,
+    /// objective names with their expected points
+    pub expected: Vec<(String, usize)>,
+}
+
+/// an objective scoring differently than the fixture expects
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub objective: String,
+    pub expected: usize,
+    pub actual: usize,
+}
+
+impl FromStr for Fixture {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect();
+        if lines.len() < Grid::SIZE {
+            return Err("A fixture starts with a board of 11 rows");
+        }
+        let (board, scores) = lines.split_at(Grid::SIZE);
+
+        let grid = board.join("\n").parse()?;
+        let expected = scores
+            .iter()
+            .map(|line| {
+                let (name, points) = line
+                    .rsplit_once('=')
+                    .ok_or("Expected scores are written as `name = points`")?;
+                let points = points
+                    .trim()
+                    .parse()
+                    .map_err(|_| "Points have to be a number")?;
+                Ok((name.trim().to_string(), points))
+            })
+            .collect::<Result<_, &'static str>>()?;
+        Ok(Self { grid, expected })
+    }
+}
+
+impl Fixture {
+    /// scores the board with every objective named in the fixture
+    pub fn mismatches(&self) -> Result<Vec<Mismatch>, &'static str> {
+        let objectives = all_objectives();
+        let mut result = Vec::default();
+        for (name, expected) in self.expected.iter() {
+            let objective = objectives
+                .iter()
+                .find(|objective| objective.name() == name)
+                .ok_or("There is no objective with this name")?;
+            let actual = objective.score(&self.grid).points();
+            if actual != *expected {
+                result.push(Mismatch {
+                    objective: name.clone(),
+                    expected: *expected,
+                    actual,
+                });
+            }
+        }
+        Ok(result)
+    }
+}
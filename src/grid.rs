@@ -1,16 +1,30 @@
-use crate::asset_management::{AssetID, AssetManager};
+use crate::area::{AreaID, AreaInfo, Areas};
+use crate::asset_management::AssetID;
+use crate::bitboard::{Bitboard, Layers};
 use crate::card::RuinIndicator;
-use crate::shape::{Geometry, Shape};
+use crate::config::MapConfig;
+use crate::fingerprint::{transform, Fingerprint};
+use crate::shape::{Geometry, Orientation, Shape};
 use crate::util::to_array;
-use bevy::math::i32;
+#[cfg(feature = "game")]
+use crate::{
+    asset_management::AssetManager,
+    board_code::BoardCode,
+    config::GameConfig,
+    objective::{CoinSource, GameObjectives},
+    players::{Players, Seat},
+};
+#[cfg(feature = "game")]
 use bevy::prelude::*;
-use bevy::utils::{HashMap, HashSet};
+use bevy_math::{IVec2, Vec2};
+use bevy_utils::tracing as log;
 use derive_deref::*;
 use itertools::Itertools;
-use serde::Deserialize;
-use std::cmp::{min, Ordering};
-use std::collections::VecDeque;
-use std::ops::{Add, RangeFrom};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::Add;
+use std::str::FromStr;
 
 const SPRITE_SIZE: f32 = 75.;
 const GRID_SIZE: usize = 11;
@@ -65,7 +79,7 @@ impl Add<Coordinate> for Coordinate {
     }
 }
 
-#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Cultivation {
     //Add a None type??
     Village,
@@ -75,6 +89,30 @@ pub enum Cultivation {
     Goblin,
 }
 
+impl Cultivation {
+    /// the character of the cultivation in boards written as text, uppercase on ruins
+    pub fn symbol(&self) -> char {
+        match self {
+            Cultivation::Village => 'v',
+            Cultivation::Water => 'w',
+            Cultivation::Farm => 'a',
+            Cultivation::Forest => 'f',
+            Cultivation::Goblin => 'g',
+        }
+    }
+
+    pub fn from_symbol(symbol: char) -> Option<Self> {
+        match symbol.to_ascii_lowercase() {
+            'v' => Some(Cultivation::Village),
+            'w' => Some(Cultivation::Water),
+            'a' => Some(Cultivation::Farm),
+            'f' => Some(Cultivation::Forest),
+            'g' => Some(Cultivation::Goblin),
+            _ => None,
+        }
+    }
+}
+
 impl AssetID for Cultivation {
     fn asset_id(&self) -> &'static str {
         match self {
@@ -90,7 +128,7 @@ impl AssetID for Cultivation {
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Terrain {
     Normal,
-    Mountain(bool), // stores whether coin is already collected
+    Mountain(bool), // stores whether the coin is still there
     Ruin,
 }
 
@@ -119,25 +157,17 @@ impl AssetID for Terrain {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct CultivationInformation {
     cultivation: Cultivation,
-    area_id: AreaID,
 }
 
 impl CultivationInformation {
     pub fn cultivation(&self) -> &Cultivation {
         &self.cultivation
     }
-
-    pub fn area_id(&self) -> AreaID {
-        self.area_id
-    }
 }
 
 impl From<Cultivation> for CultivationInformation {
     fn from(c: Cultivation) -> Self {
-        Self {
-            cultivation: c,
-            area_id: AreaID(0),
-        }
+        Self { cultivation: c }
     }
 }
 
@@ -145,15 +175,16 @@ impl From<Cultivation> for CultivationInformation {
 pub struct Field {
     pub cultivation: Option<CultivationInformation>,
     terrain: Terrain,
-    entity: Entity,
     position: Coordinate,
 }
-struct FieldComponent;
+
+#[cfg(feature = "game")]
+/// the sprite showing the field at the coordinate
+pub struct FieldSprite(Coordinate);
 
 impl Field {
-    fn new(entity: Entity, position: Coordinate) -> Self {
+    fn new(position: Coordinate) -> Self {
         Field {
-            entity,
             terrain: Terrain::default(),
             cultivation: Option::default(),
             position,
@@ -171,49 +202,74 @@ impl Field {
     pub fn is_free(&self) -> bool {
         !self.terrain.is_mountain() && self.cultivation.is_none()
     }
-}
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct AreaInfo {
-    pub kind: Cultivation,
-    pub field_coords: Vec<Coordinate>,
+    /// the character of the field in boards written as text, see `Grid::from_str`
+    pub fn symbol(&self) -> char {
+        match (&self.cultivation, self.terrain) {
+            (Some(info), Terrain::Ruin) => info.cultivation.symbol().to_ascii_uppercase(),
+            (Some(info), _) => info.cultivation.symbol(),
+            (None, Terrain::Mountain(true)) => 'M',
+            (None, Terrain::Mountain(false)) => 'm',
+            (None, Terrain::Ruin) => 'R',
+            (None, Terrain::Normal) => '.',
+        }
+    }
 }
 
-impl AreaInfo {
-    pub fn size(&self) -> usize {
-        self.field_coords.len()
+impl AssetID for Field {
+    fn asset_id(&self) -> &'static str {
+        match &self.cultivation {
+            Some(info) => info.cultivation.asset_id(),
+            None => self.terrain.asset_id(),
+        }
     }
 }
 
-impl PartialOrd for AreaInfo {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.size().partial_cmp(&other.size())
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldChange {
+    Cultivation {
+        old: Option<Cultivation>,
+        new: Option<Cultivation>,
+    },
+    Terrain {
+        old: Terrain,
+        new: Terrain,
+    },
+    CoinCollected,
 }
 
-impl Ord for AreaInfo {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.size().cmp(&other.size())
-    }
+/// Sent for every change of a field, so the rules never have to care about rendering, sound or the like
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldChanged {
+    pub position: Coordinate,
+    pub change: FieldChange,
 }
 
-impl From<Cultivation> for AreaInfo {
-    fn from(c: Cultivation) -> Self {
-        Self {
-            kind: c,
-            field_coords: Vec::default(),
-        }
-    }
+/// Where and how a geometry can be placed. The fields are `anchor + offset` for every offset of the oriented geometry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Placement {
+    pub orientation: Orientation,
+    pub anchor: Coordinate,
 }
 
-#[derive(Deref, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct AreaID(usize);
+impl Placement {
+    pub fn fields(&self, geom: &Geometry) -> Vec<Coordinate> {
+        self.orientation
+            .apply(geom)
+            .iter()
+            .map(|&offset| self.anchor + offset)
+            .collect()
+    }
+}
 
-#[derive(Debug)]
 pub struct Grid {
-    entity: Entity,
-    area_infos: HashMap<AreaID, AreaInfo>,
-    area_counter: RangeFrom<usize>,
+    areas: Areas,
+    layers: Layers,
+    fingerprint: Fingerprint,
+    // only the grid of the game records its changes, see `track_changes`
+    tracks_changes: bool,
+    // not yet sent as `FieldChanged` events
+    changes: Vec<FieldChanged>,
     inner: [Field; Grid::SIZE * Grid::SIZE],
 }
 
@@ -266,23 +322,69 @@ impl Grid {
         }
     }
 
+    pub fn is_cultivated(&self, coord: &Coordinate) -> bool {
+        if let Ok(index) = self.index(coord) {
+            self.inner[index].cultivation.is_some()
+        } else {
+            false
+        }
+    }
+
     pub fn try_cultivate(
         &mut self,
         shape: &Shape,
         coord: &Coordinate,
-        assets: &AssetManager,
-        mut handles: &mut Query<&mut Handle<ColorMaterial>>,
     ) -> Result<Vec<Coordinate>, &'static str> {
         if self.accepts_geometry_at(shape.geometry(), coord, &shape.ruin()) {
-            self.cultivate(shape, coord, assets, &mut handles);
+            self.cultivate(shape, coord);
             Ok(shape.geometry().iter().map(|pos| *pos + *coord).collect())
         } else {
+            log::debug!(
+                "can't place\n{}\nat ({}, {}) on\n{}",
+                shape.geometry(),
+                coord.x,
+                coord.y,
+                self
+            );
             Err("Can't place the shape here")
         }
     }
 
-    fn next_area_id(&mut self) -> AreaID {
-        AreaID(self.area_counter.next().unwrap())
+    /// overwrites already cultivated fields with the cultivation of `shape`
+    pub fn try_recultivate(
+        &mut self,
+        shape: &Shape,
+        coord: &Coordinate,
+    ) -> Result<Vec<Coordinate>, &'static str> {
+        if !self.accepts_recultivation_at(shape.geometry(), coord) {
+            return Err("Only cultivated fields without goblins can be recultivated");
+        }
+
+        let new = shape.cultivation();
+        for &position in shape.geometry().iter() {
+            let position = *coord + position;
+            let old = self
+                .at_mut(&position)
+                .unwrap()
+                .cultivation
+                .replace(new.into())
+                .map(|info| info.cultivation);
+            if let Some(old) = old {
+                self.fingerprint.toggle_cultivation(&position, old);
+            }
+            self.fingerprint.toggle_cultivation(&position, new);
+            self.layers.cultivate(&position, new);
+            self.record(
+                position,
+                FieldChange::Cultivation {
+                    old,
+                    new: Some(new),
+                },
+            );
+        }
+        // areas might fall apart, which the disjoint-set cannot express, but recultivation is rare
+        self.rebuild_areas();
+        Ok(shape.geometry().iter().map(|pos| *pos + *coord).collect())
     }
 
     pub fn mountain_coins(&mut self) -> Vec<Coordinate> {
@@ -297,9 +399,7 @@ impl Grid {
                 continue;
             }
 
-            let field = self.at_mut(&coord).unwrap();
-            if let Terrain::Mountain(ref mut coin @ true) = field.terrain {
-                *coin = false;
+            if self.collect_coin(&coord) {
                 result.push(coord);
             }
         }
@@ -307,67 +407,127 @@ impl Grid {
         result
     }
 
-    fn cultivate(
-        &mut self,
-        shape: &Shape,
-        coord: &Coordinate,
-        assets: &AssetManager,
-        handles: &mut Query<&mut Handle<ColorMaterial>>,
-    ) {
-        for position in shape.geometry().iter() {
-            let mut field = self.at_mut(&(*coord + *position)).unwrap();
-            field.cultivation = Some(shape.cultivation().into());
-            let mut handle = handles.get_mut(field.entity).unwrap();
-            *handle = assets.fetch(shape.cultivation().asset_id()).unwrap();
+    /// false, if there is no mountain with a coin left
+    fn collect_coin(&mut self, coord: &Coordinate) -> bool {
+        let field = self.at_mut(coord).unwrap();
+        if let Terrain::Mountain(ref mut coin @ true) = field.terrain {
+            *coin = false;
+            self.fingerprint
+                .toggle_terrain(coord, Terrain::Mountain(true));
+            self.fingerprint
+                .toggle_terrain(coord, Terrain::Mountain(false));
+            self.record(*coord, FieldChange::CoinCollected);
+            true
+        } else {
+            false
         }
+    }
 
-        let id = self.next_area_id();
-        self.area_infos
-            .insert(id, AreaInfo::from(shape.cultivation()));
-        self.propagate_id(coord, id, &shape.cultivation());
+    fn cultivate(&mut self, shape: &Shape, coord: &Coordinate) {
+        for position in shape.geometry().iter() {
+            let position = *coord + *position;
+            let field = self.at_mut(&position).unwrap();
+            let old = field.cultivation.replace(shape.cultivation().into());
+            self.record(
+                position,
+                FieldChange::Cultivation {
+                    old: old.map(|info| info.cultivation),
+                    new: Some(shape.cultivation()),
+                },
+            );
+            self.areas.insert(&position, shape.cultivation());
+            self.layers.cultivate(&position, shape.cultivation());
+            self.fingerprint
+                .toggle_cultivation(&position, shape.cultivation());
+        }
     }
 
-    fn propagate_id(&mut self, coord: &Coordinate, id: AreaID, cultivation: &Cultivation) {
-        let mut queue = VecDeque::new();
-        let mut fields = Vec::new();
-        queue.push_front(*coord);
-        while let Some(pos) = queue.pop_back() {
-            fields.push(pos);
+    fn record(&mut self, position: Coordinate, change: FieldChange) {
+        if self.tracks_changes {
+            self.changes.push(FieldChanged { position, change });
+        }
+    }
 
-            // non valid `pos` are not added to the queue
-            self.at_mut(&pos)
-                .as_mut()
-                .unwrap()
-                .cultivation
-                .as_mut()
-                .unwrap()
-                .area_id = id;
-
-            let mut area_ids_to_remove: HashSet<AreaID> = HashSet::default(); // we are immutable iterating over neighbors, we cannot remove the AreaIDs on the fly
-            for field in self.neighbors(&pos) {
-                if let Some(area_info) = field.cultivation {
-                    if area_info.area_id() < id && area_info.cultivation == *cultivation {
-                        queue.push_front(field.position());
-                        // the old id has been flooded, time to delete if from known id's (if not happened in earlier iteration)
-                        area_ids_to_remove.insert(area_info.area_id());
-                    }
-                }
+    /// Records the changes from now on, to be sent as `FieldChanged` events.
+    /// The fields as they are now are recorded as changes of an empty field, so the renderer catches up.
+    pub fn track_changes(&mut self) {
+        self.tracks_changes = true;
+        let fields: Vec<(Coordinate, Terrain, Option<Cultivation>)> = self
+            .all()
+            .map(|field| {
+                let cultivation = field.cultivation.map(|info| info.cultivation);
+                (field.position(), field.terrain, cultivation)
+            })
+            .collect();
+        for (position, terrain, cultivation) in fields {
+            if terrain != Terrain::Normal {
+                let change = FieldChange::Terrain {
+                    old: Terrain::Normal,
+                    new: terrain,
+                };
+                self.record(position, change);
             }
+            if cultivation.is_some() {
+                let change = FieldChange::Cultivation {
+                    old: None,
+                    new: cultivation,
+                };
+                self.record(position, change);
+            }
+        }
+    }
 
-            for id in area_ids_to_remove.iter() {
-                self.area_infos.remove_entry(id);
+    /// Shows `next` instead of this grid: `next` records the changes from now on, this grid stops.
+    /// The fields that differ are recorded as changes from this grid to `next`, so the renderer catches up.
+    pub fn hand_over_changes(&mut self, next: &mut Grid) {
+        // changes not yet sent come first, they lead to the fields of this grid
+        next.changes = std::mem::take(&mut self.changes);
+        next.tracks_changes = self.tracks_changes;
+        self.tracks_changes = false;
+
+        let mut changes = Vec::default();
+        for (old, new) in self.inner.iter().zip(next.inner.iter()) {
+            if old.terrain != new.terrain {
+                let change = FieldChange::Terrain {
+                    old: old.terrain,
+                    new: new.terrain,
+                };
+                changes.push((new.position, change));
+            }
+            let (old_cultivation, new_cultivation) = (
+                old.cultivation.map(|info| info.cultivation),
+                new.cultivation.map(|info| info.cultivation),
+            );
+            if old_cultivation != new_cultivation {
+                let change = FieldChange::Cultivation {
+                    old: old_cultivation,
+                    new: new_cultivation,
+                };
+                changes.push((new.position, change));
             }
         }
+        for (position, change) in changes {
+            next.record(position, change);
+        }
+    }
 
-        fields.sort();
-        fields.dedup();
-        self.area_infos.insert(
-            id,
-            AreaInfo {
-                kind: *cultivation,
-                field_coords: fields,
-            },
-        );
+    pub fn has_changes(&self) -> bool {
+        !self.changes.is_empty()
+    }
+
+    /// the changes since the last call
+    pub fn drain_changes(&mut self) -> impl Iterator<Item = FieldChanged> + '_ {
+        self.changes.drain(..)
+    }
+
+    fn rebuild_areas(&mut self) {
+        let mut areas = Areas::default();
+        for field in self.all() {
+            if let Some(info) = field.cultivation {
+                areas.insert(&field.position(), info.cultivation);
+            }
+        }
+        self.areas = areas;
     }
 
     pub fn mountains(&self) -> impl Iterator<Item = &Field> {
@@ -391,41 +551,102 @@ impl Grid {
 
     /// returns ids of components sorted by size (biggest first)
     pub fn area_ids(&self, cultivation: Cultivation) -> impl Iterator<Item = (&AreaID, &AreaInfo)> {
-        (&self.area_infos)
+        self.areas
             .iter()
             .filter(move |&(_, info)| info.kind == cultivation)
             .sorted_by(|lhs, rhs| lhs.1.cmp(rhs.1))
             .rev()
     }
 
+    /// every field next to the area, each of them once
     pub fn area_neighbors(&self, id: &AreaID) -> impl Iterator<Item = &Field> {
-        self.area_infos
-            .get(id)
+        self.areas
+            .info(id)
             .unwrap()
-            .field_coords
+            .boundary()
             .iter()
-            .map(move |&field| self.neighbors(&field))
-            .flatten()
-            .dedup()
+            .map(move |coord| self.at(coord).unwrap())
+    }
+
+    pub fn area_of(&self, coord: &Coordinate) -> Option<AreaID> {
+        if self.is_valid_coord(coord) {
+            self.areas.area_of(coord)
+        } else {
+            None
+        }
+    }
+
+    pub fn area(&self, id: &AreaID) -> Option<&AreaInfo> {
+        self.areas.info(id)
+    }
+
+    /// ids of the distinct areas touching the area `id`
+    pub fn neighbor_areas(&self, id: &AreaID) -> Vec<AreaID> {
+        self.areas.neighbor_areas(id)
     }
 
     pub fn accepts_geometry(&self, geom: &Geometry, ruins: &RuinIndicator) -> bool {
-        // little bit ugly as the grid assumes knowledge on what orientations the geometry can have, but hm
-        let mut mirrored = geom.clone();
-        mirrored.mirror();
-        for mut geom in [geom.clone(), mirrored] {
+        // rotating in place instead of collecting the orientations, this is called for every card
+        let mut geom = geom.clone();
+        for _ in 0..2 {
             for _ in 0..4 {
-                for field in self.all() {
-                    if self.accepts_geometry_at(&geom, &field.position, ruins) {
-                        return true;
-                    }
+                if !self.legal_anchors(&geom, ruins).is_empty() {
+                    return true;
                 }
                 geom.rotate_clockwise();
             }
+            geom.mirror();
         }
         false
     }
 
+    /// every anchor the geometry can be placed at, as it is oriented right now
+    pub fn legal_anchors(&self, geom: &Geometry, ruins: &RuinIndicator) -> Bitboard {
+        self.layers.legal_anchors(geom, ruins)
+    }
+
+    /// Every distinct way to place the geometry. Orientations that only reproduce the same shape
+    /// (like rotations of the splinter or of a straight line) are reported once.
+    pub fn legal_placements(&self, geom: &Geometry, ruins: &RuinIndicator) -> Vec<Placement> {
+        geom.orientations()
+            .iter()
+            .flat_map(|(orientation, oriented)| {
+                let orientation = *orientation;
+                self.legal_anchors(oriented, ruins)
+                    .iter()
+                    .map(move |anchor| Placement {
+                        orientation,
+                        anchor,
+                    })
+            })
+            .collect()
+    }
+
+    pub fn layers(&self) -> &Layers {
+        &self.layers
+    }
+
+    pub fn areas(&self) -> &Areas {
+        &self.areas
+    }
+
+    pub fn fingerprint(&self) -> &Fingerprint {
+        &self.fingerprint
+    }
+
+    /// whether both boards show the same position, possibly mirrored or rotated along a symmetry of the map
+    pub fn is_equivalent(&self, other: &Grid) -> bool {
+        self.fingerprint.canonical() == other.fingerprint.canonical()
+            && self.fingerprint.symmetries().iter().any(|orientation| {
+                self.all().all(|field| {
+                    let other = other
+                        .at(&transform(orientation, &field.position()))
+                        .unwrap();
+                    field.terrain == other.terrain && field.cultivation == other.cultivation
+                })
+            })
+    }
+
     pub fn accepts_geometry_at(
         &self,
         geom: &Geometry,
@@ -445,32 +666,57 @@ impl Grid {
         !(**ruins) || on_ruin
     }
 
-    fn initialize(com: &mut Commands, ruins: &[Coordinate], mountains: &[Coordinate]) -> Self {
+    /// goblins are not driven out by recultivating, that would remove their monster penalty
+    pub fn accepts_recultivation_at(&self, geom: &Geometry, coord: &Coordinate) -> bool {
+        geom.iter().all(|&pos| {
+            let cultivation = self
+                .at(&(pos + *coord))
+                .ok()
+                .and_then(|field| field.cultivation.map(|info| *info.cultivation()));
+            matches!(cultivation, Some(cultivation) if cultivation != Cultivation::Goblin)
+        })
+    }
+
+    fn initialize(ruins: &[Coordinate], mountains: &[Coordinate]) -> Self {
         let mut temp_vec: Vec<Field> = Vec::default();
         for y in 0..Self::SIZE {
             for x in 0..Self::SIZE {
-                let entity = com.spawn().id();
-                temp_vec.push(Field::new(entity, (x, y).into()));
+                temp_vec.push(Field::new((x, y).into()));
             }
         }
-        let entity = com.spawn().id();
         let mut grid = Grid {
-            entity,
-            area_infos: Default::default(),
-            area_counter: 1..,
+            areas: Areas::default(),
+            layers: Layers::default(),
+            fingerprint: Fingerprint::new(Vec::default()),
+            tracks_changes: false,
+            changes: Vec::default(),
             inner: to_array::<Field, { Self::SIZE * Self::SIZE }>(temp_vec),
         };
 
         for pos in mountains.iter() {
-            grid.inner[grid.index(pos).unwrap()].terrain = Terrain::Mountain(true);
+            grid.set_terrain(pos, Terrain::Mountain(true));
+            grid.layers.add_mountain(pos);
         }
 
         for pos in ruins.iter() {
-            grid.inner[grid.index(pos).unwrap()].terrain = Terrain::Ruin;
+            grid.set_terrain(pos, Terrain::Ruin);
+            grid.layers.add_ruin(pos);
+        }
+
+        grid.fingerprint = Fingerprint::new(Fingerprint::map_symmetries(&grid));
+        for field in grid.inner.iter() {
+            grid.fingerprint
+                .toggle_terrain(&field.position(), field.terrain);
         }
         grid
     }
 
+    fn set_terrain(&mut self, coord: &Coordinate, terrain: Terrain) {
+        let field = self.at_mut(coord).unwrap();
+        let old = std::mem::replace(&mut field.terrain, terrain);
+        self.record(*coord, FieldChange::Terrain { old, new: terrain });
+    }
+
     pub fn at_mut(&mut self, coord: &Coordinate) -> Result<&mut Field, ()> {
         self.index(coord).map(move |i| &mut self.inner[i])
     }
@@ -479,23 +725,12 @@ impl Grid {
         self.index(coord).map(|i| &self.inner[i])
     }
 
-    pub fn new(com: &mut Commands) -> Self {
-        let mountains: Vec<Coordinate> = vec![
-            (2, 2).into(),
-            (3, 9).into(),
-            (5, 5).into(),
-            (7, 1).into(),
-            (8, 8).into(),
-        ]; //(x,y)
-        let ruins: Vec<Coordinate> = vec![
-            (1, 2).into(),
-            (1, 8).into(),
-            (5, 1).into(),
-            (5, 9).into(),
-            (9, 2).into(),
-            (9, 8).into(),
-        ];
-        Grid::initialize(com, &ruins, &mountains)
+    pub fn new() -> Self {
+        Self::from_map(&MapConfig::default())
+    }
+
+    pub fn from_map(map: &MapConfig) -> Self {
+        Grid::initialize(&map.ruins, &map.mountains)
     }
 
     pub fn all(&self) -> impl Iterator<Item = &Field> {
@@ -519,52 +754,186 @@ impl Grid {
     pub fn column(&self, nth: usize) -> impl Iterator<Item = &Field> {
         self.inner.iter().skip(nth).step_by(Self::SIZE)
     }
+}
 
-    /// from left border to bottom
-    pub fn nth_diagonal(&self, nth: usize) -> impl Iterator<Item = &Field> {
-        let delta_x = min(nth, Self::SIZE - 1);
-        let delta_y = if nth > (Self::SIZE - 1) {
-            nth - (Self::SIZE - 1)
-        } else {
-            0
-        }; // to avoid underflow of usize
+/// the board as read by `Grid::from_str`
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in (0..Self::SIZE).rev() {
+            let row: String = self.row(y).map(Field::symbol).collect();
+            write!(f, "{}", row)?;
+            if y != 0 {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
 
-        self.inner
-            .iter()
-            .skip(delta_x + delta_y * Self::SIZE)
-            .step_by(Self::SIZE - 1)
-            // If nth > Self::Size the iterator ends in the uppermost row, yielding no more Items
-            .take(nth + 1)
+// the fields are what matters in test failures, areas and layers follow from them
+impl fmt::Debug for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Grid(")?;
+        writeln!(f, "{}", self)?;
+        write!(f, ")")
     }
+}
 
-    /// from left border to bottom
-    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &Field>> {
-        (0..(Self::SIZE * Self::SIZE - 1))
-            .into_iter()
-            .map(move |nth| self.nth_diagonal(nth))
+/// One line per row, the top row first:
+/// `.` free land, `R` ruin, `M` mountain, `m` mountain whose coin is collected,
+/// `v` village, `w` water, `a` farm, `f` forest and `g` goblin. Cultivated ruins are written in uppercase.
+impl FromStr for Grid {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows: Vec<&str> = s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        if rows.len() != Self::SIZE {
+            return Err("A board has 11 rows");
+        }
+
+        let mut map = MapConfig {
+            mountains: Vec::default(),
+            ruins: Vec::default(),
+        };
+        let mut cultivated = Vec::default();
+        let mut collected = Vec::default();
+        for (nth, row) in rows.iter().enumerate() {
+            if row.chars().count() != Self::SIZE {
+                return Err("A row has 11 fields");
+            }
+            let y = Self::SIZE - 1 - nth;
+            for (x, symbol) in row.chars().enumerate() {
+                let pos: Coordinate = (x, y).into();
+                match symbol {
+                    '.' => {}
+                    'R' => map.ruins.push(pos),
+                    'M' => map.mountains.push(pos),
+                    'm' => {
+                        map.mountains.push(pos);
+                        collected.push(pos);
+                    }
+                    _ => {
+                        let cultivation =
+                            Cultivation::from_symbol(symbol).ok_or("Unknown field in board")?;
+                        if symbol.is_uppercase() {
+                            map.ruins.push(pos);
+                        }
+                        cultivated.push((pos, cultivation));
+                    }
+                }
+            }
+        }
+
+        let mut grid = Grid::from_map(&map);
+        for (pos, cultivation) in cultivated {
+            let field = Shape::new(&Geometry::default(), &cultivation, &false.into(), false);
+            grid.try_cultivate(&field, &pos)?;
+        }
+        for pos in collected {
+            grid.collect_coin(&pos);
+        }
+        Ok(grid)
     }
 }
 
-pub fn init_grid(mut com: Commands, assets: Res<AssetManager>) {
-    let grid = Grid::new(&mut com);
+#[cfg(feature = "game")]
+/// every player starts on the same map, the ones after the first wait in `Players`
+pub fn init_grid(
+    mut com: Commands,
+    config: Res<GameConfig>,
+    mut objectives: ResMut<GameObjectives>,
+) {
+    // an invalid code starts the empty map, see `KarthographPlugin`
+    let start = || match config
+        .position
+        .as_ref()
+        .map(|code| code.parse::<BoardCode>())
+    {
+        Some(Ok(code)) => code.grid,
+        _ => Grid::from_map(&config.map),
+    };
+    // coins of shapes are not part of a position, the ones of mountains are
+    let add_coins = |grid: &Grid, objectives: &mut GameObjectives| {
+        for mountain in grid.mountains() {
+            if mountain.terrain() == Terrain::Mountain(false) {
+                objectives.add_coin(vec![mountain.position()], CoinSource::Mountain);
+            }
+        }
+    };
+
+    let mut grid = start();
+    add_coins(&grid, &mut objectives);
+    let others = (1..config.players)
+        .map(|_| {
+            let grid = start();
+            let mut objectives = objectives.share();
+            add_coins(&grid, &mut objectives);
+            Seat { grid, objectives }
+        })
+        .collect();
+    grid.track_changes();
+    com.insert_resource(grid);
+    com.insert_resource(Players::new(others));
+}
 
-    for field in grid.all() {
-        let mat = assets.fetch(field.terrain.asset_id()).unwrap();
-        let pos = field.position();
-        //THE FIELD OR THE GRID SHOULD DO THIS ITSELF
-        com.entity(field.entity)
-            .insert(FieldComponent)
-            .insert_bundle(SpriteBundle {
+#[cfg(feature = "game")]
+/// the materials follow with the `FieldChanged` events of the new grid
+pub fn spawn_field_sprites(mut com: Commands, assets: Res<AssetManager>) {
+    for y in 0..Grid::SIZE {
+        for x in 0..Grid::SIZE {
+            let pos: Coordinate = (x, y).into();
+            com.spawn_bundle(SpriteBundle {
                 sprite: Sprite::new(Vec2::new(SPRITE_SIZE, SPRITE_SIZE)),
-                material: mat,
+                material: assets.fetch(Terrain::Normal.asset_id()).unwrap(),
                 transform: Transform::from_xyz(
                     pos.x as f32 * SPRITE_SIZE + GRID_OFFSET,
                     pos.y as f32 * SPRITE_SIZE + GRID_OFFSET,
                     -0.1,
                 ),
                 ..Default::default()
-            });
+            })
+            .insert(FieldSprite(pos));
+        }
+    }
+}
+
+#[cfg(feature = "game")]
+/// passes the changes of the grid on as events
+pub fn send_field_changes(grid: Option<ResMut<Grid>>, mut events: EventWriter<FieldChanged>) {
+    if let Some(mut grid) = grid {
+        // only borrow mutably if needed, so the grid is not marked as changed every frame
+        if grid.has_changes() {
+            for change in grid.drain_changes() {
+                events.send(change);
+            }
+        }
     }
+}
 
-    com.insert_resource(grid);
+#[cfg(feature = "game")]
+pub fn render_fields(
+    mut events: EventReader<FieldChanged>,
+    grid: Option<Res<Grid>>,
+    assets: Res<AssetManager>,
+    mut sprites: Query<(&FieldSprite, &mut Handle<ColorMaterial>)>,
+) {
+    let grid = match grid {
+        Some(grid) => grid,
+        None => return,
+    };
+    let changed: Vec<Coordinate> = events.iter().map(|event| event.position).collect();
+    if changed.is_empty() {
+        return;
+    }
+    for (sprite, mut handle) in sprites.iter_mut() {
+        if changed.contains(&sprite.0) {
+            *handle = assets
+                .fetch(grid.at(&sprite.0).unwrap().asset_id())
+                .unwrap();
+        }
+    }
 }
@@ -0,0 +1,372 @@
+use std::{collections::VecDeque, thread, time::Duration};
+
+use bevy::{
+    asset::AssetPlugin,
+    input::{
+        keyboard::KeyboardInput,
+        mouse::{MouseButtonInput, MouseScrollUnit, MouseWheel},
+        ElementState, InputPlugin, InputSystem,
+    },
+    prelude::*,
+    text::Font,
+    transform::TransformPlugin,
+    window::{CursorMoved, WindowId},
+};
+
+use crate::{
+    asset_management::AssetManager,
+    board_code::BoardCode,
+    card::{Card, CardClickEvent, CardOption},
+    card_pile::CardPile,
+    config::GameConfig,
+    grid::{Coordinate, Grid},
+    objective::GameObjectives,
+    players::Players,
+    plugin::KarthographPlugin,
+    seasons::Season,
+    shape::Shape,
+    skill::{BuySkill, Skills},
+    summary::{GameRecord, GameSummary},
+    svg, GameState,
+};
+
+// loading the card pile from disk takes a few frames, the steps themselves only one or two each
+const LOADING_FRAMES: usize = 5000;
+const FRAMES_PER_STEP: usize = 100;
+
+/// Marks an app without window and renderer. The assets are reduced to what the rules need.
+pub struct Headless;
+
+/// One input of the player. Every step is turned into the events a mouse or keyboard would send,
+/// so it runs through the same systems as a real game.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Step {
+    /// picks the option of the revealed card, the left one is 0
+    Choose(usize),
+    /// left click wherever the cursor is
+    Click,
+    /// moves the shape in hand onto the field
+    Move(Coordinate),
+    RotateClockwise,
+    RotateCounterClockwise,
+    Mirror,
+    /// places the shape in hand where it was moved to
+    Place,
+    /// buys the revealed skill with this index for the card on the table
+    BuySkill(usize),
+    /// closes the scoring screen at the end of a season
+    Continue,
+}
+
+/// the steps still to be played, one per frame
+#[derive(Default)]
+pub struct Script {
+    steps: VecDeque<Step>,
+    error: Option<&'static str>,
+}
+
+impl Script {
+    pub fn push(&mut self, step: Step) {
+        self.steps.push_back(step);
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub fn error(&self) -> Option<&'static str> {
+        self.error
+    }
+
+    fn fail(&mut self, reason: &'static str) {
+        self.error = Some(reason);
+        self.steps.clear();
+    }
+}
+
+/// The parts of the engine the game needs besides the `MinimalPlugins`, and the `Script` feeding the input.
+/// Add it before the `KarthographPlugin` with `ui` turned off.
+pub struct HeadlessPlugin;
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.insert_resource(Headless)
+            .init_resource::<Script>()
+            .add_plugin(TransformPlugin)
+            .add_plugin(InputPlugin)
+            .add_plugin(AssetPlugin)
+            .add_asset::<ColorMaterial>()
+            .add_asset::<Font>()
+            .add_event::<CursorMoved>()
+            // before the input is processed, so every step is seen by the game in the same frame
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                play_script.system().before(InputSystem),
+            );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn play_script(
+    mut script: ResMut<Script>,
+    state: Res<State<GameState>>,
+    options: Query<(&CardOption, &GlobalTransform)>,
+    shapes: Query<&Shape>,
+    mut cursor: EventWriter<CursorMoved>,
+    mut buttons: EventWriter<MouseButtonInput>,
+    mut wheel: EventWriter<MouseWheel>,
+    mut keys: EventWriter<KeyboardInput>,
+    mut skills: EventWriter<BuySkill>,
+) {
+    let step = match script.steps.front() {
+        Some(step) => *step,
+        None => return,
+    };
+    let in_season = *state.current() == GameState::SeasonState;
+    let has_shape = shapes.iter().next().is_some();
+
+    let mut move_cursor = |position: Vec2| {
+        cursor.send(CursorMoved {
+            id: WindowId::primary(),
+            position,
+        })
+    };
+    let mut click = |button: MouseButton| {
+        for state in [ElementState::Pressed, ElementState::Released] {
+            buttons.send(MouseButtonInput { button, state });
+        }
+    };
+
+    match step {
+        Step::Choose(idx) => {
+            // wait for the card to be revealed
+            if !in_season || options.iter().next().is_none() {
+                return;
+            }
+            match options.iter().find(|(option, _)| ***option == idx) {
+                Some((_, transform)) => {
+                    move_cursor(transform.translation.truncate());
+                    // the cursor has to be there, before the click arrives
+                    script.steps[0] = Step::Click;
+                }
+                None => script.fail("The card has no such option"),
+            }
+            return;
+        }
+        Step::Click if in_season => click(MouseButton::Left),
+        Step::Move(coord) if in_season && has_shape => move_cursor(Grid::grid_to_screen(coord)),
+        Step::RotateClockwise | Step::RotateCounterClockwise if in_season && has_shape => wheel
+            .send(MouseWheel {
+                unit: MouseScrollUnit::Line,
+                x: 0.,
+                y: if step == Step::RotateClockwise {
+                    -1.
+                } else {
+                    1.
+                },
+            }),
+        Step::Mirror if in_season && has_shape => click(MouseButton::Middle),
+        Step::Place if in_season && has_shape => click(MouseButton::Left),
+        Step::BuySkill(idx) if in_season && options.iter().next().is_some() => {
+            skills.send(BuySkill(idx))
+        }
+        Step::Continue if *state.current() == GameState::SeasonScoreState => {
+            for state in [ElementState::Pressed, ElementState::Released] {
+                keys.send(KeyboardInput {
+                    scan_code: 0,
+                    key_code: Some(KeyCode::Space),
+                    state,
+                });
+            }
+        }
+        // not possible yet, wait for the game to catch up
+        _ => return,
+    }
+    script.steps.pop_front();
+}
+
+/// A game without window, driven by `Step`s. Meant for tests:
+/// ```ignore
+/// let mut game = HeadlessGame::new(GameConfig::default());
+/// game.load()?;
+/// game.play(vec![Step::Choose(0), Step::Move((4, 4).into()), Step::Place])?;
+/// assert!(game.grid().is_cultivated(&(4, 4).into()));
+/// ```
+pub struct HeadlessGame {
+    pub app: App,
+}
+
+impl HeadlessGame {
+    pub fn new(config: GameConfig) -> Self {
+        let mut builder = App::build();
+        builder
+            .add_plugins(MinimalPlugins)
+            .add_plugin(HeadlessPlugin)
+            .add_plugin(KarthographPlugin { config, ui: false });
+        Self { app: builder.app }
+    }
+
+    /// runs until the assets are loaded and the first card is revealed
+    pub fn load(&mut self) -> Result<(), &'static str> {
+        if let Some(code) = &self
+            .app
+            .world
+            .get_resource::<GameConfig>()
+            .unwrap()
+            .position
+        {
+            code.parse::<BoardCode>()?;
+        }
+        for _ in 0..LOADING_FRAMES {
+            self.app.update();
+            if self.state() != GameState::Loading && self.card().is_some() {
+                return Ok(());
+            }
+            // the card pile is loaded on another thread
+            thread::sleep(Duration::from_millis(1));
+        }
+        Err("The game did not finish loading")
+    }
+
+    /// plays the steps one after another, fails if the game gets stuck on one of them
+    pub fn play(&mut self, steps: impl IntoIterator<Item = Step>) -> Result<(), &'static str> {
+        let mut frames = 0;
+        {
+            let mut script = self.app.world.get_resource_mut::<Script>().unwrap();
+            for step in steps {
+                script.push(step);
+                frames += FRAMES_PER_STEP;
+            }
+        }
+        for _ in 0..frames {
+            if self.script().is_done() {
+                break;
+            }
+            self.app.update();
+        }
+        // one more frame, so the last step has been handled by the game
+        self.app.update();
+
+        if let Some(reason) = self.script().error() {
+            return Err(reason);
+        }
+        if !self.script().is_done() {
+            return Err("The game got stuck on a step");
+        }
+        Ok(())
+    }
+
+    fn script(&self) -> &Script {
+        self.app.world.get_resource::<Script>().unwrap()
+    }
+
+    pub fn state(&self) -> GameState {
+        *self
+            .app
+            .world
+            .get_resource::<State<GameState>>()
+            .unwrap()
+            .current()
+    }
+
+    pub fn grid(&self) -> &Grid {
+        self.app.world.get_resource::<Grid>().unwrap()
+    }
+
+    /// the card pile as it was loaded, before it is shuffled
+    pub fn card_pile(&self) -> &CardPile {
+        let cards = &self.app.world.get_resource::<AssetManager>().unwrap().cards;
+        self.app
+            .world
+            .get_resource::<Assets<CardPile>>()
+            .unwrap()
+            .get(cards)
+            .unwrap()
+    }
+
+    pub fn objectives(&self) -> &GameObjectives {
+        self.app.world.get_resource::<GameObjectives>().unwrap()
+    }
+
+    pub fn season(&self) -> &Season {
+        self.app.world.get_resource::<Season>().unwrap()
+    }
+
+    /// the card on the table
+    pub fn card(&mut self) -> Option<Card> {
+        self.app
+            .world
+            .query::<&Card>()
+            .iter(&self.app.world)
+            .next()
+            .cloned()
+    }
+
+    /// the shapes offered by the card on the table, ordered like `Step::Choose` counts them
+    pub fn options(&mut self) -> Vec<Shape> {
+        let mut options: Vec<(usize, Shape)> = self
+            .app
+            .world
+            .query::<(&CardOption, &CardClickEvent)>()
+            .iter(&self.app.world)
+            .map(|(option, event)| match event {
+                CardClickEvent::SpawnShape(shape) => (**option, shape.clone()),
+            })
+            .collect();
+        // shapes spread over several fields are clickable on each of them
+        options.sort_by_key(|(idx, _)| *idx);
+        options.dedup_by_key(|(idx, _)| *idx);
+        options.into_iter().map(|(_, shape)| shape).collect()
+    }
+
+    pub fn summary(&self) -> GameSummary {
+        GameSummary::new(
+            self.app.world.get_resource::<GameConfig>().unwrap(),
+            self.app.world.get_resource::<GameRecord>().unwrap(),
+            self.grid(),
+            self.objectives(),
+            self.players(),
+        )
+    }
+
+    /// the players waiting for their turn, `grid` and `objectives` belong to the one on turn
+    pub fn players(&self) -> &Players {
+        self.app.world.get_resource::<Players>().unwrap()
+    }
+
+    /// the position with the seed and the cards played so far, see `BoardCode`
+    pub fn board_code(&mut self) -> String {
+        // the card on the table is not played yet
+        let on_table = self.card().is_some() as usize;
+        let record = self.app.world.get_resource::<GameRecord>().unwrap();
+        // every player places the same cards, the code holds the map of the player on turn
+        let player = self.players().current();
+        let turns = record
+            .turns
+            .iter()
+            .filter(|turn| turn.player == player)
+            .count();
+        let revealed = record.skipped + turns;
+        let seed = self.app.world.get_resource::<GameConfig>().unwrap().seed;
+        BoardCode::encode(self.grid(), seed, Some((revealed - on_table) as u16))
+    }
+
+    /// the map with its legend, see `svg::render`
+    pub fn svg(&self) -> String {
+        svg::render(self.grid(), self.objectives())
+    }
+
+    pub fn skills(&self) -> &Skills {
+        self.app.world.get_resource::<Skills>().unwrap()
+    }
+
+    /// the shape chosen from the card, not yet placed
+    pub fn shape(&mut self) -> Option<Shape> {
+        self.app
+            .world
+            .query::<&Shape>()
+            .iter(&self.app.world)
+            .next()
+            .cloned()
+    }
+}
@@ -0,0 +1,51 @@
+pub use config::{GameConfig, MapConfig};
+#[cfg(feature = "game")]
+pub use plugin::{
+    CardPlugin, GridPlugin, KarthographPlugin, ObjectivePlugin, SeasonPlugin, UiPlugin,
+};
+
+pub mod analytics;
+pub mod area;
+pub mod asset_management;
+pub mod bitboard;
+pub mod board_code;
+pub mod bot;
+pub mod card;
+pub mod card_pile;
+pub mod config;
+#[cfg(feature = "game")]
+pub mod env;
+pub mod events;
+pub mod fingerprint;
+pub mod fixture;
+pub mod grid;
+#[cfg(feature = "game")]
+pub mod headless;
+#[cfg(feature = "game")]
+pub mod mouse;
+pub mod objective;
+pub mod players;
+#[cfg(feature = "game")]
+pub mod plugin;
+pub mod seasons;
+pub mod shape;
+pub mod skill;
+#[cfg(feature = "game")]
+pub mod summary;
+pub mod svg;
+#[cfg(feature = "game")]
+pub mod ui;
+pub mod util;
+
+pub const SPRITE_SIZE: f32 = 75.;
+pub const GRID_SIZE: usize = 11;
+//x=y offset
+pub const GRID_OFFSET: f32 = SPRITE_SIZE;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameState {
+    Loading,
+    SeasonState,
+    SeasonScoreState,
+    End,
+}
@@ -5,29 +5,58 @@ use std::{
 };
 
 use crate::{
+    analytics::{Diagonal, Occupancy},
     asset_management::AssetID,
-    grid::{Coordinate, Cultivation, Grid},
+    grid::{Coordinate, Cultivation, Field, Grid, Terrain},
     seasons::SeasonType,
+    util::to_array,
 };
-use bevy::utils::HashMap;
-use itertools::Itertools;
-use rand::{prelude::SliceRandom, thread_rng};
+use bevy_utils::{HashMap, HashSet};
+use rand::{prelude::SliceRandom, thread_rng, Rng};
 
 /* I really like this too, but its unintuitive when reading
 struct Objective{scoring: fn(&Grid)->u32,}*/
 
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Score(usize);
+
+impl Score {
+    pub fn points(&self) -> usize {
+        self.0
+    }
+}
 impl AddAssign<usize> for Score {
     fn add_assign(&mut self, rhs: usize) {
         self.0 += rhs
     }
 }
 
+#[derive(Debug, Clone)]
 pub struct SeasonScore {
     pub a: (&'static str, Score),
     pub b: (&'static str, Score),
     pub coin_count: usize,
+    pub monster_penalty: usize,
+}
+
+impl SeasonScore {
+    // the monsters can push a season below zero
+    pub fn total(&self) -> isize {
+        (self.a.1.points() + self.b.1.points() + self.coin_count) as isize
+            - self.monster_penalty as isize
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoinSource {
+    Shape,
+    Mountain,
+}
+
+#[derive(Debug, Clone)]
+pub struct Coin {
+    pub source: CoinSource,
+    pub position: Vec<Coordinate>,
 }
 
 impl fmt::Display for Score {
@@ -39,16 +68,140 @@ impl fmt::Display for Score {
     }
 }
 
+/// A part of the grid, that is scored independently of all other parts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Part {
+    Field(Coordinate),
+    Row(usize),
+    Column(usize),
+    Diagonal(Diagonal, usize),
+}
+
 pub trait Objective: AssetID {
     fn name(&self) -> &'static str;
     //fn description(&self) -> &'static str;
     fn score(&self, grid: &Grid) -> Score;
+
+    /// Objectives summing up independent parts of the grid return the parts, whose score may change with the field at `coord`.
+    /// `None` means, that the whole grid has to be scored again.
+    fn parts_affected_by(&self, _coord: &Coordinate) -> Option<Vec<Part>> {
+        None
+    }
+
+    /// Only called for parts returned by `parts_affected_by`, to keep the score up to date.
+    /// Summed up over all parts it equals `score`, which scores the whole grid directly.
+    fn part_score(&self, _grid: &Grid, _part: &Part) -> usize {
+        0
+    }
+
+    /// An optimistic maximum of the score, if at most `fields_left` free fields are cultivated from now on.
+    /// Recultivating already cultivated fields is not taken into account.
+    fn upper_bound(&self, grid: &Grid, fields_left: usize) -> Score;
+}
+
+/// A guess of the fields, that can be cultivated in `time`, to show the bounds of a season while it is played:
+/// every time and the card in hand (already part of the passed time) count as a card of five fields placed twice.
+/// It is not a limit, splinters take no time and skills add fields, so bounds derived from it may be exceeded.
+pub fn estimated_fields_in_time(time: i32) -> usize {
+    (time.max(0) as usize + 1) * 5 * 2
+}
+
+fn reachable_fields(grid: &Grid, fields_left: usize) -> usize {
+    min(fields_left, grid.layers().free().count())
+}
+
+/// the sum of the `fields_left` biggest gains
+fn best_gains(mut gains: Vec<usize>, fields_left: usize) -> usize {
+    gains.sort_unstable_by(|lhs, rhs| rhs.cmp(lhs));
+    gains.iter().take(fields_left).sum()
+}
+
+/// how many lines can be completed, if each one is still `missing` some free fields
+fn completable_lines(mut missing: Vec<usize>, fields_left: usize) -> usize {
+    missing.sort_unstable();
+    let mut left = fields_left;
+    missing
+        .iter()
+        .take_while(|&&count| {
+            let fits = count <= left;
+            left = left.saturating_sub(count);
+            fits
+        })
+        .count()
+}
+
+fn all_parts(objective: &dyn Objective, grid: &Grid) -> HashSet<Part> {
+    grid.all()
+        .filter_map(|field| objective.parts_affected_by(&field.position()))
+        .flatten()
+        .collect()
+}
+
+fn with_neighbors(coord: &Coordinate) -> Vec<Part> {
+    [(0, 0), (0, 1), (0, -1), (1, 0), (-1, 0)]
+        .iter()
+        .map(|&delta| *coord + delta.into())
+        .filter(|pos| {
+            (0..Grid::SIZE as i32).contains(&pos.x) && (0..Grid::SIZE as i32).contains(&pos.y)
+        })
+        .map(Part::Field)
+        .collect()
+}
+
+fn row_and_column(coord: &Coordinate) -> Vec<Part> {
+    vec![Part::Row(coord.y as usize), Part::Column(coord.x as usize)]
+}
+
+/// The score of an objective, kept up to date with the fields changed by every placement
+#[derive(Debug, Default)]
+struct TrackedScore {
+    score: Score,
+    parts: HashMap<Part, usize>,
+    initialized: bool,
+}
+
+impl TrackedScore {
+    fn reset(&mut self, objective: &dyn Objective, grid: &Grid) {
+        self.parts = all_parts(objective, grid)
+            .into_iter()
+            .map(|part| (part, objective.part_score(grid, &part)))
+            .collect();
+        self.score = objective.score(grid);
+        self.initialized = true;
+    }
+
+    fn update(&mut self, objective: &dyn Objective, grid: &Grid, changed: &[Coordinate]) {
+        if !self.initialized {
+            return self.reset(objective, grid);
+        }
+
+        let affected: Option<HashSet<Part>> = changed
+            .iter()
+            .map(|coord| objective.parts_affected_by(coord))
+            .collect::<Option<Vec<_>>>()
+            .map(|parts| parts.into_iter().flatten().collect());
+        let affected = match affected {
+            Some(affected) => affected,
+            None => {
+                self.score = objective.score(grid);
+                return;
+            }
+        };
+
+        for part in affected {
+            let new = objective.part_score(grid, &part);
+            let old = self.parts.insert(part, new).unwrap_or(0);
+            self.score = Score(self.score.0 + new - old);
+        }
+    }
 }
 
 pub struct GameObjectives {
     objectives: [Box<dyn Objective + Send + Sync>; 4],
+    tracked: [TrackedScore; 4],
     scores: [Option<SeasonScore>; 4],
-    current_coins: Vec<Vec<Coordinate>>,
+    current_coins: Vec<Coin>,
+    spent_coins: usize,
 }
 impl GameObjectives {
     pub fn objectives_for_season(&self, season: &SeasonType) -> (&dyn Objective, &dyn Objective) {
@@ -58,6 +211,13 @@ impl GameObjectives {
         )
     }
 
+    /// the four objectives of the game, in the order their seasons pick them
+    pub fn objectives(&self) -> impl Iterator<Item = &dyn Objective> {
+        self.objectives
+            .iter()
+            .map(|objective| &**objective as &dyn Objective)
+    }
+
     fn idx(season: &SeasonType) -> usize {
         match &season {
             SeasonType::Spring => 0,
@@ -67,8 +227,62 @@ impl GameObjectives {
         }
     }
 
-    pub fn add_coin(&mut self, position: Vec<Coordinate>) {
-        self.current_coins.push(position);
+    pub fn add_coin(&mut self, position: Vec<Coordinate>, source: CoinSource) {
+        self.current_coins.push(Coin { source, position });
+    }
+
+    pub fn coins_from(&self, source: CoinSource) -> usize {
+        self.current_coins
+            .iter()
+            .filter(|coin| coin.source == source)
+            .count()
+    }
+
+    /// collected coins, that have not been spent on skills
+    pub fn coins(&self) -> usize {
+        self.current_coins.len() - self.spent_coins
+    }
+
+    pub fn spend_coins(&mut self, amount: usize) -> Result<(), &'static str> {
+        if amount > self.coins() {
+            return Err("Not enough coins");
+        }
+        self.spent_coins += amount;
+        Ok(())
+    }
+
+    pub fn refund_coins(&mut self, amount: usize) {
+        self.spent_coins -= min(amount, self.spent_coins);
+    }
+
+    /// rescores the objectives after a placement, only the parts of the grid touched by `changed` if possible
+    pub fn update_scores(&mut self, grid: &Grid, changed: &[Coordinate]) {
+        for (objective, tracked) in self.objectives.iter().zip(self.tracked.iter_mut()) {
+            tracked.update(objective.as_ref(), grid, changed);
+        }
+    }
+
+    /// what the objectives of the season would yield right now, `None` before the first placement
+    pub fn current_scores(&self, season: &SeasonType) -> (Option<Score>, Option<Score>) {
+        let current = |idx: usize| {
+            let tracked = &self.tracked[idx % 4];
+            tracked.initialized.then(|| tracked.score)
+        };
+        (current(Self::idx(season)), current(Self::idx(season) + 1))
+    }
+
+    /// the optimistic maxima of the objectives of the season, if `fields_left` fields can still be cultivated
+    pub fn upper_bounds(
+        &self,
+        season: &SeasonType,
+        grid: &Grid,
+        fields_left: usize,
+    ) -> (Score, Score) {
+        let (first, second) = self.objectives_for_season(season);
+        (
+            first.upper_bound(grid, fields_left),
+            second.upper_bound(grid, fields_left),
+        )
     }
 
     pub fn score_season(&mut self, season: &SeasonType, grid: &Grid) -> &SeasonScore {
@@ -78,35 +292,70 @@ impl GameObjectives {
             self.scores[idx] = Some(SeasonScore {
                 a: (first.name(), first.score(grid)),
                 b: (second.name(), second.score(grid)),
-                coin_count: self.current_coins.len(),
+                coin_count: self.coins(),
+                monster_penalty: monster_penalty(grid),
             });
         }
 
         self.scores[idx].as_ref().unwrap()
     }
+
+    pub fn season_score(&self, season: &SeasonType) -> Option<&SeasonScore> {
+        self.scores[Self::idx(season)].as_ref()
+    }
+
+    /// sum of all seasons scored so far
+    pub fn total_score(&self) -> isize {
+        self.scores.iter().flatten().map(SeasonScore::total).sum()
+    }
+}
+
+/// every free field next to a goblin costs one point each season
+pub fn monster_penalty(grid: &Grid) -> usize {
+    grid.all()
+        .filter(|field| {
+            field.is_free()
+                && grid.neighbors(&field.position()).any(|neighbor| {
+                    neighbor.cultivation.as_ref().map(|info| info.cultivation())
+                        == Some(&Cultivation::Goblin)
+                })
+        })
+        .count()
 }
 
 impl Default for GameObjectives {
     fn default() -> Self {
-        let mut objectives: Vec<Box<dyn Objective + Send + Sync>> = vec![
-            Box::new(PfadDesWaldes),
-            Box::new(Metropole),
-            Box::new(SchildDesReichs),
-            Box::new(AusgedehnteStraende),
-            Box::new(Gruenflaeche),
-            Box::new(Grenzland),
-            Box::new(GoldenCorn),
-            Box::new(TalDerMagier),
-            Box::new(LongRoad),
-            Box::new(DuesterWald),
-            Box::new(SchillerndeEbene),
-            Box::new(Schildwald),
-            Box::new(DieKessel),
-            Box::new(UnzugaenglicheBaronie),
-            Box::new(Bewaesserungskanal),
-            Box::new(BastionInTheWilderness),
-        ];
-        objectives.shuffle(&mut thread_rng());
+        Self::new(&mut thread_rng())
+    }
+}
+
+/// every objective of the game, once
+pub fn all_objectives() -> Vec<Box<dyn Objective + Send + Sync>> {
+    vec![
+        Box::new(PfadDesWaldes),
+        Box::new(Metropole),
+        Box::new(SchildDesReichs),
+        Box::new(AusgedehnteStraende),
+        Box::new(Gruenflaeche),
+        Box::new(Grenzland),
+        Box::new(GoldenCorn),
+        Box::new(TalDerMagier),
+        Box::new(LongRoad),
+        Box::new(DuesterWald),
+        Box::new(SchillerndeEbene),
+        Box::new(Schildwald),
+        Box::new(DieKessel),
+        Box::new(UnzugaenglicheBaronie),
+        Box::new(Bewaesserungskanal),
+        Box::new(BastionInTheWilderness),
+    ]
+}
+
+impl GameObjectives {
+    /// draws four of the objectives
+    pub fn new(rng: &mut impl Rng) -> Self {
+        let mut objectives = all_objectives();
+        objectives.shuffle(rng);
 
         Self {
             objectives: [
@@ -115,8 +364,30 @@ impl Default for GameObjectives {
                 objectives.pop().unwrap(),
                 objectives.pop().unwrap(),
             ],
+            tracked: Default::default(),
             scores: Default::default(),
             current_coins: Default::default(),
+            spent_coins: 0,
+        }
+    }
+
+    /// the same objectives for the map of another player, without its coins and scores
+    pub fn share(&self) -> Self {
+        let mut all = all_objectives();
+        let objectives = self.objectives().map(|objective| {
+            let idx = all
+                .iter()
+                .position(|other| other.name() == objective.name())
+                .unwrap();
+            all.swap_remove(idx)
+        });
+
+        Self {
+            objectives: to_array(objectives.collect()),
+            tracked: Default::default(),
+            scores: Default::default(),
+            current_coins: Default::default(),
+            spent_coins: 0,
         }
     }
 }
@@ -150,6 +421,27 @@ impl Objective for DuesterWald {
         }
         Score(count)
     }
+
+    fn parts_affected_by(&self, coord: &Coordinate) -> Option<Vec<Part>> {
+        Some(with_neighbors(coord))
+    }
+
+    fn part_score(&self, grid: &Grid, part: &Part) -> usize {
+        match part {
+            Part::Field(coord) => {
+                let field = grid.at(coord).unwrap();
+                let is_forest = field.cultivation.as_ref().map(|info| info.cultivation())
+                    == Some(&Cultivation::Forest);
+                (is_forest && !grid.neighbors(coord).any(|neighbor| neighbor.is_free())) as usize
+            }
+            _ => 0,
+        }
+    }
+
+    fn upper_bound(&self, grid: &Grid, fields_left: usize) -> Score {
+        let forests = grid.layers().cultivation(Cultivation::Forest).count();
+        Score(forests + reachable_fields(grid, fields_left))
+    }
 }
 
 struct TalDerMagier;
@@ -180,6 +472,38 @@ impl Objective for TalDerMagier {
 
         score
     }
+
+    fn parts_affected_by(&self, coord: &Coordinate) -> Option<Vec<Part>> {
+        Some(with_neighbors(coord))
+    }
+
+    fn part_score(&self, grid: &Grid, part: &Part) -> usize {
+        match part {
+            Part::Field(coord) if grid.at(coord).unwrap().terrain().is_mountain() => grid
+                .neighbors(coord)
+                .filter(|neighbor| {
+                    matches!(
+                        neighbor.cultivation.as_ref().map(|info| info.cultivation()),
+                        Some(Cultivation::Water) | Some(Cultivation::Farm)
+                    )
+                })
+                .count(),
+            _ => 0,
+        }
+    }
+
+    fn upper_bound(&self, grid: &Grid, fields_left: usize) -> Score {
+        let gains = grid
+            .all()
+            .filter(|field| field.is_free())
+            .map(|field| {
+                grid.neighbors(&field.position())
+                    .filter(|neighbor| neighbor.terrain().is_mountain())
+                    .count()
+            })
+            .collect();
+        Score(self.score(grid).0 + best_gains(gains, fields_left))
+    }
 }
 
 struct LongRoad;
@@ -196,21 +520,47 @@ impl Objective for LongRoad {
     }
 
     fn score(&self, grid: &Grid) -> Score {
-        let mut score = Score::default();
-        for diagonal in grid.diagonals().take(Grid::SIZE) {
-            let mut is_free = false;
-            for field in diagonal {
-                if field.is_free() {
-                    is_free = true;
-                }
-            }
+        // the diagonals touching the left and the bottom border
+        Score(
+            grid.diagonals(Diagonal::Falling)
+                .take(Grid::SIZE)
+                .map(|mut diagonal| diagonal.all(|field| !field.is_free()))
+                .filter(|&complete| complete)
+                .count()
+                * 3,
+        )
+    }
 
-            if !is_free {
-                score += 3;
+    // the diagonals touching the left and the bottom border
+    fn parts_affected_by(&self, coord: &Coordinate) -> Option<Vec<Part>> {
+        let nth = (coord.x + coord.y) as usize;
+        if nth < Grid::SIZE {
+            Some(vec![Part::Diagonal(Diagonal::Falling, nth)])
+        } else {
+            Some(Vec::default())
+        }
+    }
+
+    fn part_score(&self, grid: &Grid, part: &Part) -> usize {
+        match *part {
+            Part::Diagonal(direction, nth) => {
+                if grid.diagonal(direction, nth).all(|field| !field.is_free()) {
+                    3
+                } else {
+                    0
+                }
             }
+            _ => 0,
         }
+    }
 
-        score
+    fn upper_bound(&self, grid: &Grid, fields_left: usize) -> Score {
+        let missing = grid
+            .diagonals(Diagonal::Falling)
+            .take(Grid::SIZE)
+            .map(|diagonal| diagonal.filter(|field| field.is_free()).count())
+            .collect();
+        Score(completable_lines(missing, fields_left) * 3)
     }
 }
 
@@ -235,6 +585,12 @@ impl Objective for BastionInTheWilderness {
                 * 8,
         )
     }
+
+    fn upper_bound(&self, grid: &Grid, fields_left: usize) -> Score {
+        // every village of six fields is worth eight points
+        let villages = grid.layers().cultivation(Cultivation::Village).count();
+        Score((villages + reachable_fields(grid, fields_left)) / 6 * 8)
+    }
 }
 
 struct Metropole;
@@ -251,17 +607,19 @@ impl Objective for Metropole {
     }
 
     fn score(&self, grid: &Grid) -> Score {
-        'outer: for (&id, info) in grid.area_ids(Cultivation::Village) {
-            let mut neighbors = grid.area_neighbors(&id);
-            for mountain in grid.mountains() {
-                if neighbors.contains(&mountain) {
-                    continue 'outer;
-                }
-            }
+        // the biggest village not touching a mountain
+        grid.area_ids(Cultivation::Village)
+            .find(|(id, _)| {
+                !grid
+                    .area_neighbors(id)
+                    .any(|field| field.terrain().is_mountain())
+            })
+            .map_or_else(Score::default, |(_, info)| Score(info.size()))
+    }
 
-            return Score(info.size());
-        }
-        Score::default()
+    fn upper_bound(&self, grid: &Grid, fields_left: usize) -> Score {
+        let villages = grid.layers().cultivation(Cultivation::Village).count();
+        Score(villages + reachable_fields(grid, fields_left))
     }
 }
 
@@ -296,6 +654,50 @@ impl Objective for GoldenCorn {
         }
         score
     }
+
+    fn parts_affected_by(&self, coord: &Coordinate) -> Option<Vec<Part>> {
+        Some(with_neighbors(coord))
+    }
+
+    fn part_score(&self, grid: &Grid, part: &Part) -> usize {
+        let ruin = match part {
+            Part::Field(coord) if grid.is_ruin(coord) => grid.at(coord).unwrap(),
+            _ => return 0,
+        };
+        let mut score = 0;
+        if ruin.cultivation.as_ref().map(|info| info.cultivation()) == Some(&Cultivation::Farm) {
+            score += 3;
+        }
+
+        for neighbor in grid.neighbors(&ruin.position()) {
+            if neighbor.cultivation.as_ref().map(|info| info.cultivation())
+                == Some(&Cultivation::Water)
+            {
+                score += 1;
+            }
+        }
+        score
+    }
+
+    fn upper_bound(&self, grid: &Grid, fields_left: usize) -> Score {
+        // a free ruin is either worth three as farm or one per neighboring ruin as water
+        let gains = grid
+            .all()
+            .filter(|field| field.is_free())
+            .map(|field| {
+                let as_water = grid
+                    .neighbors(&field.position())
+                    .filter(|neighbor| neighbor.terrain() == Terrain::Ruin)
+                    .count();
+                if field.terrain() == Terrain::Ruin {
+                    max(3, as_water)
+                } else {
+                    as_water
+                }
+            })
+            .collect();
+        Score(self.score(grid).0 + best_gains(gains, fields_left))
+    }
 }
 
 struct Grenzland;
@@ -326,6 +728,36 @@ impl Objective for Grenzland {
         }
         score
     }
+
+    fn parts_affected_by(&self, coord: &Coordinate) -> Option<Vec<Part>> {
+        Some(row_and_column(coord))
+    }
+
+    fn part_score(&self, grid: &Grid, part: &Part) -> usize {
+        let full = match *part {
+            Part::Row(nth) => grid.row(nth).all(|field| !field.is_free()),
+            Part::Column(nth) => grid.column(nth).all(|field| !field.is_free()),
+            _ => false,
+        };
+        if full {
+            6
+        } else {
+            0
+        }
+    }
+
+    fn upper_bound(&self, grid: &Grid, fields_left: usize) -> Score {
+        let rows = grid
+            .rows()
+            .map(|row| row.filter(|field| field.is_free()).count())
+            .collect();
+        let columns = grid
+            .columns()
+            .map(|column| column.filter(|field| field.is_free()).count())
+            .collect();
+        // rows and columns share their fields, so each of them may use up all fields left
+        Score((completable_lines(rows, fields_left) + completable_lines(columns, fields_left)) * 6)
+    }
 }
 
 struct Gruenflaeche;
@@ -360,6 +792,43 @@ impl Objective for Gruenflaeche {
         }
         score
     }
+
+    fn parts_affected_by(&self, coord: &Coordinate) -> Option<Vec<Part>> {
+        Some(row_and_column(coord))
+    }
+
+    fn part_score(&self, grid: &Grid, part: &Part) -> usize {
+        let is_forest = |field: &Field| {
+            field.cultivation.as_ref().map(|f| f.cultivation()) == Some(&Cultivation::Forest)
+        };
+        let forested = match *part {
+            Part::Row(nth) => grid.row(nth).any(is_forest),
+            Part::Column(nth) => grid.column(nth).any(is_forest),
+            _ => false,
+        };
+        forested as usize
+    }
+
+    fn upper_bound(&self, grid: &Grid, fields_left: usize) -> Score {
+        let is_forest = |field: &Field| {
+            field.cultivation.as_ref().map(|f| f.cultivation()) == Some(&Cultivation::Forest)
+        };
+        // lines without forest, that still have a free field
+        let open = |line: Vec<&Field>| {
+            !line.iter().any(|field| is_forest(field)) && line.iter().any(|field| field.is_free())
+        };
+        let rows = grid
+            .rows()
+            .map(|row| open(row.collect()))
+            .filter(|&is_open| is_open)
+            .count();
+        let columns = grid
+            .columns()
+            .map(|column| open(column.collect()))
+            .filter(|&is_open| is_open)
+            .count();
+        Score(self.score(grid).0 + min(rows, fields_left) + min(columns, fields_left))
+    }
 }
 
 struct AusgedehnteStraende;
@@ -376,44 +845,28 @@ impl Objective for AusgedehnteStraende {
     }
 
     fn score(&self, grid: &Grid) -> Score {
-        let mut score = Score::default();
-        for (&id, info) in grid.area_ids(Cultivation::Water) {
-            let mut neighbors = grid.area_neighbors(&id);
-            if neighbors.any(|f| {
-                f.cultivation.as_ref().map(|f| f.cultivation()) == Some(&Cultivation::Farm)
-            }) {
-                continue;
-            }
-
-            if info
-                .field_coords
-                .iter()
-                .any(|pos| grid.neighbors(pos).count() < 4)
-            {
-                continue;
-            }
-            score += 3;
-        }
-
-        for (&id, info) in grid.area_ids(Cultivation::Farm) {
-            let mut neighbors = grid.area_neighbors(&id);
-            if neighbors.any(|f| {
-                f.cultivation.as_ref().map(|f| f.cultivation()) == Some(&Cultivation::Water)
-            }) {
-                continue;
-            }
-
-            if info
-                .field_coords
-                .iter()
-                .any(|pos| grid.neighbors(pos).count() < 4)
-            {
-                continue;
-            }
-            score += 3;
-        }
+        // areas neither touching the other cultivation nor the border of the map
+        let lonely = |kind: Cultivation, other: Cultivation| {
+            grid.area_ids(kind)
+                .filter(|(id, info)| {
+                    !grid.neighbor_cultivations(id).contains(&other)
+                        && info
+                            .field_coords
+                            .iter()
+                            .all(|pos| Grid::distance_to_edge(pos) > 0)
+                })
+                .count()
+        };
+        Score(
+            (lonely(Cultivation::Water, Cultivation::Farm)
+                + lonely(Cultivation::Farm, Cultivation::Water))
+                * 3,
+        )
+    }
 
-        score
+    fn upper_bound(&self, grid: &Grid, fields_left: usize) -> Score {
+        // every new area takes at least one field
+        Score(self.score(grid).0 + 3 * reachable_fields(grid, fields_left))
     }
 }
 
@@ -437,6 +890,12 @@ impl Objective for SchildDesReichs {
             Score::default()
         }
     }
+
+    fn upper_bound(&self, grid: &Grid, fields_left: usize) -> Score {
+        // the second largest village has at most half of all village fields
+        let villages = grid.layers().cultivation(Cultivation::Village).count();
+        Score((villages + reachable_fields(grid, fields_left)) / 2)
+    }
 }
 
 struct SchillerndeEbene;
@@ -455,20 +914,18 @@ impl Objective for SchillerndeEbene {
     fn score(&self, grid: &Grid) -> Score {
         let mut score = Score::default();
         for (id, _) in grid.area_ids(Cultivation::Village) {
-            if grid
-                .area_neighbors(id)
-                .filter_map(|f| f.cultivation.as_ref().map(|f| f.cultivation()))
-                .sorted()
-                .dedup()
-                .count()
-                >= 3
-            {
+            if grid.neighbor_cultivations(id).len() >= 3 {
                 score += 3;
             }
         }
 
         score
     }
+
+    fn upper_bound(&self, grid: &Grid, fields_left: usize) -> Score {
+        let villages = grid.area_ids(Cultivation::Village).count();
+        Score(3 * (villages + reachable_fields(grid, fields_left)))
+    }
 }
 
 struct UnzugaenglicheBaronie;
@@ -485,28 +942,27 @@ impl Objective for UnzugaenglicheBaronie {
     }
 
     fn score(&self, grid: &Grid) -> Score {
-        // stores the biggest square having this field as bottom right corner
-        let mut matrix = [[0; Grid::SIZE]; Grid::SIZE];
-        let mut result = 0;
-        for x in 0..Grid::SIZE {
-            for y in 0..Grid::SIZE {
-                // safe: we are within the gridsize
-                if grid.at(&(x, y).into()).unwrap().is_free() {
-                    continue;
-                }
-                let left = if x > 0 { matrix[x - 1][y] } else { 0 };
-                let right = if y > 0 { matrix[x][y - 1] } else { 0 };
-                let diagonal = if left > 0 && right > 0 {
-                    matrix[x - 1][y - 1]
-                } else {
-                    0
-                };
+        Score(grid.largest_square(Occupancy::Filled).width)
+    }
 
-                matrix[x][y] = 1 + min(min(left, right), diagonal);
-                result = max(result, matrix[x][y]);
-            }
-        }
-        Score(result)
+    fn upper_bound(&self, grid: &Grid, fields_left: usize) -> Score {
+        // the biggest square, that has no more free fields than can still be filled
+        let free = grid.layers().free();
+        let fillable = |size: usize, x: usize, y: usize| {
+            (x..x + size)
+                .flat_map(|x| (y..y + size).map(move |y| (x, y)))
+                .filter(|&pos| free.contains(&pos.into()))
+                .count()
+                <= fields_left
+        };
+        let size = (1..=Grid::SIZE)
+            .rev()
+            .find(|&size| {
+                (0..=Grid::SIZE - size)
+                    .any(|x| (0..=Grid::SIZE - size).any(|y| fillable(size, x, y)))
+            })
+            .unwrap_or(0);
+        Score(size)
     }
 }
 
@@ -524,16 +980,16 @@ impl Objective for DieKessel {
     }
 
     fn score(&self, grid: &Grid) -> Score {
-        Score(
-            grid.all()
-                .filter(|field| {
-                    field.is_free()
-                        && grid
-                            .neighbors(&field.position())
-                            .all(|neigh| !neigh.is_free())
-                })
-                .count(),
-        )
+        Score(grid.enclosed_regions(1).count())
+    }
+
+    fn upper_bound(&self, grid: &Grid, fields_left: usize) -> Score {
+        // a filled field can close at most four cauldrons
+        let free = grid.layers().free().count();
+        Score(min(
+            self.score(grid).0 + 4 * reachable_fields(grid, fields_left),
+            free,
+        ))
     }
 }
 
@@ -551,43 +1007,39 @@ impl Objective for Schildwald {
     }
 
     fn score(&self, grid: &Grid) -> Score {
-        let mut score = Score::default();
-
-        // top and bottom row
-        score += grid
-            .row(0)
-            .filter(|&field| {
-                field.cultivation.as_ref().map(|i| i.cultivation()) == Some(&Cultivation::Forest)
-            })
-            .count();
+        Score(
+            grid.all()
+                .filter(|field| {
+                    Grid::distance_to_edge(&field.position()) == 0
+                        && field.cultivation.as_ref().map(|i| i.cultivation())
+                            == Some(&Cultivation::Forest)
+                })
+                .count(),
+        )
+    }
 
-        score += grid
-            .row(Grid::SIZE - 1)
-            .filter(|&field| {
-                field.cultivation.as_ref().map(|i| i.cultivation()) == Some(&Cultivation::Forest)
-            })
-            .count();
+    fn parts_affected_by(&self, coord: &Coordinate) -> Option<Vec<Part>> {
+        Some(vec![Part::Field(*coord)])
+    }
 
-        // left and right column - corners
-        score += grid
-            .column(0)
-            .skip(1)
-            .take(Grid::SIZE - 2)
-            .filter(|&field| {
-                field.cultivation.as_ref().map(|i| i.cultivation()) == Some(&Cultivation::Forest)
-            })
-            .count();
+    fn part_score(&self, grid: &Grid, part: &Part) -> usize {
+        match part {
+            Part::Field(coord) => {
+                let field = grid.at(coord).unwrap();
+                (Grid::distance_to_edge(coord) == 0
+                    && field.cultivation.as_ref().map(|i| i.cultivation())
+                        == Some(&Cultivation::Forest)) as usize
+            }
+            _ => 0,
+        }
+    }
 
-        score += grid
-            .column(Grid::SIZE - 1)
-            .skip(1)
-            .take(Grid::SIZE - 2)
-            .filter(|&field| {
-                field.cultivation.as_ref().map(|i| i.cultivation()) == Some(&Cultivation::Forest)
-            })
+    fn upper_bound(&self, grid: &Grid, fields_left: usize) -> Score {
+        let free_edge = grid
+            .all()
+            .filter(|field| field.is_free() && Grid::distance_to_edge(&field.position()) == 0)
             .count();
-
-        score
+        Score(self.score(grid).0 + min(free_edge, fields_left))
     }
 }
 
@@ -625,6 +1077,36 @@ impl Objective for Bewaesserungskanal {
         }
         score
     }
+
+    fn parts_affected_by(&self, coord: &Coordinate) -> Option<Vec<Part>> {
+        Some(with_neighbors(coord))
+    }
+
+    fn part_score(&self, grid: &Grid, part: &Part) -> usize {
+        let coord = match part {
+            Part::Field(coord) => coord,
+            _ => return 0,
+        };
+        let other = match grid
+            .at(coord)
+            .unwrap()
+            .cultivation
+            .as_ref()
+            .map(|i| i.cultivation())
+        {
+            Some(Cultivation::Farm) => Cultivation::Water,
+            Some(Cultivation::Water) => Cultivation::Farm,
+            _ => return 0,
+        };
+        grid.neighbors(coord)
+            .any(|f| f.cultivation.as_ref().map(|i| i.cultivation()) == Some(&other))
+            as usize
+    }
+
+    fn upper_bound(&self, grid: &Grid, fields_left: usize) -> Score {
+        // the new field and its four neighbors may score
+        Score(self.score(grid).0 + 5 * reachable_fields(grid, fields_left))
+    }
 }
 
 struct PfadDesWaldes;
@@ -646,13 +1128,10 @@ impl Objective for PfadDesWaldes {
         let mut union_find: HashMap<Coordinate, bool> =
             grid.mountains().map(|f| (f.position(), false)).collect();
         for (forest_id, _) in grid.area_ids(Cultivation::Forest) {
+            // every neighbor of an area is yielded once
             let mut neighbor_mountains = grid
                 .area_neighbors(forest_id)
-                .filter(|f| f.terrain().is_mountain())
-                // mountains can occur more than once as neighbors of an area
-                // however, as soon as there are two different mountains we set all to true anyway
-                // so we do not need to sort before dedup()
-                .dedup();
+                .filter(|f| f.terrain().is_mountain());
             if let Some(first_mountain) = neighbor_mountains.next() {
                 for second_mountain in neighbor_mountains {
                     // the "union"
@@ -663,4 +1142,24 @@ impl Objective for PfadDesWaldes {
         }
         Score(union_find.iter().filter(|&k_v| *k_v.1).count() * 3)
     }
+
+    fn upper_bound(&self, grid: &Grid, fields_left: usize) -> Score {
+        if reachable_fields(grid, fields_left) == 0 {
+            return self.score(grid);
+        }
+        // mountains next to a free field or a forest might still be connected
+        let reachable = grid
+            .mountains()
+            .filter(|mountain| {
+                grid.neighbors(&mountain.position()).any(|neighbor| {
+                    neighbor.is_free()
+                        || neighbor.cultivation.as_ref().map(|i| i.cultivation())
+                            == Some(&Cultivation::Forest)
+                })
+            })
+            .count();
+        // one mountain alone cannot be connected
+        let reachable = if reachable >= 2 { reachable } else { 0 };
+        Score(max(self.score(grid).0, reachable * 3))
+    }
 }
@@ -0,0 +1,61 @@
+use std::collections::VecDeque;
+
+use crate::{grid::Grid, objective::GameObjectives};
+
+/// the map and the objectives of a player waiting for their turn
+pub struct Seat {
+    pub grid: Grid,
+    pub objectives: GameObjectives,
+}
+
+/// The players of a game place every card one after another.
+/// The `Grid` and `GameObjectives` resources belong to the player on turn, the others wait here.
+#[derive(Default)]
+pub struct Players {
+    current: usize,
+    waiting: VecDeque<Seat>,
+}
+
+impl Players {
+    /// `others` are the seats of the players after the first one, in the order of their turns
+    pub fn new(others: Vec<Seat>) -> Self {
+        Self {
+            current: 0,
+            waiting: others.into(),
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.waiting.len() + 1
+    }
+
+    /// the player on turn, the first one is 0
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// the players after the current one, in the order of their turns
+    pub fn waiting(&self) -> impl Iterator<Item = &Seat> {
+        self.waiting.iter()
+    }
+
+    pub fn waiting_mut(&mut self) -> impl Iterator<Item = &mut Seat> {
+        self.waiting.iter_mut()
+    }
+
+    /// Swaps the map and the objectives of the current player with the ones of the next player.
+    /// Returns whether the next player places the same card, so false once every player has placed it.
+    pub fn pass(&mut self, grid: &mut Grid, objectives: &mut GameObjectives) -> bool {
+        let mut next = match self.waiting.pop_front() {
+            Some(next) => next,
+            None => return false,
+        };
+        grid.hand_over_changes(&mut next.grid);
+        std::mem::swap(grid, &mut next.grid);
+        std::mem::swap(objectives, &mut next.objectives);
+        self.waiting.push_back(next);
+
+        self.current = (self.current + 1) % self.count();
+        self.current != 0
+    }
+}
@@ -0,0 +1,226 @@
+use bevy::{log, prelude::*};
+
+use crate::{
+    asset_management::{check_readiness, init_assets, AssetManager},
+    board_code::BoardCode,
+    card::{click_card, RuinIndicator},
+    card_pile::{initialize_cards, next_card, CardPile, CardPileLoader, NewCard, TurnsToSkip},
+    config::{GameConfig, GameRng},
+    events::*,
+    grid::{init_grid, render_fields, send_field_changes, spawn_field_sprites, FieldChanged},
+    mouse::{mouse_position, MousePosition},
+    objective::GameObjectives,
+    seasons::{advance_season, continue_after_scoring, score_season, Season},
+    shape::{mirror_shape, move_shape, place_shape, rotate_shape},
+    skill::{buy_skill, click_skill, highlight_skills, BuySkill, Skills},
+    summary::{export_summary, record_turns, GameRecord},
+    svg::export_svg,
+    ui::{
+        animate_numbers, despawn_scoring_screen, init_camera, setup_coin_ui, setup_objective_ui,
+        setup_season_timeline, setup_skill_ui, setup_ui, update_coin_track, update_score_bounds,
+        update_score_ui, update_season_timeline,
+    },
+    GameState,
+};
+
+/// The whole game. Add it after the `DefaultPlugins`.
+/// The sub-plugins rely on the config, the random numbers, the assets and the `GameState` set up here,
+/// so a custom UI is added next to this plugin with `ui` turned off.
+#[derive(Clone)]
+pub struct KarthographPlugin {
+    pub config: GameConfig,
+    pub ui: bool,
+}
+
+impl Default for KarthographPlugin {
+    fn default() -> Self {
+        Self {
+            config: GameConfig::default(),
+            ui: true,
+        }
+    }
+}
+
+impl Plugin for KarthographPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        let mut config = self.config.clone();
+        // a position continues the game it was taken from, as far as the code knows it
+        let mut skipped = TurnsToSkip::default();
+        let code = config
+            .position
+            .as_ref()
+            .map(|code| code.parse::<BoardCode>());
+        if let Some(Err(reason)) = &code {
+            // `HeadlessGame::load` and `GameConfig::from_args` reject the code before
+            log::error!(
+                "GameConfig::position: {}, the game starts on the empty map",
+                reason
+            );
+        }
+        if let Some(Ok(code)) = code {
+            config.seed = code.seed.or(config.seed);
+            skipped = TurnsToSkip(code.turn.unwrap_or(0) as usize);
+        }
+        // a drawn seed is kept, so the game can be reported and replayed
+        config.seed.get_or_insert_with(rand::random);
+
+        app.insert_resource(GameRng(config.rng()))
+            .insert_resource(GameRecord {
+                skipped: skipped.0,
+                ..Default::default()
+            })
+            .insert_resource(skipped)
+            .insert_resource(config)
+            .insert_resource(AssetManager::default())
+            .add_state(GameState::Loading)
+            .add_system_set(
+                SystemSet::on_enter(GameState::Loading).with_system(init_assets.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::Loading).with_system(check_readiness.system()),
+            )
+            .add_plugin(GridPlugin)
+            .add_plugin(CardPlugin)
+            .add_plugin(SeasonPlugin)
+            .add_plugin(ObjectivePlugin);
+        if self.ui {
+            app.add_plugin(UiPlugin);
+        }
+    }
+}
+
+/// the map and its changes
+pub struct GridPlugin;
+
+impl Plugin for GridPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_event::<FieldChanged>()
+            .add_system_set(SystemSet::on_exit(GameState::Loading).with_system(init_grid.system()))
+            .add_system(send_field_changes.system());
+    }
+}
+
+/// drawing cards, choosing an option and placing its shape
+pub struct CardPlugin;
+
+impl Plugin for CardPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_event::<NewCard>()
+            .add_event::<CardRevealed>()
+            .add_event::<OptionChosen>()
+            .add_event::<ShapePlaced>()
+            .add_event::<CoinGained>()
+            .add_event::<SkillBought>()
+            .add_event::<SkillRefunded>()
+            .insert_resource(RuinIndicator::default())
+            .insert_resource(MousePosition::default())
+            .add_asset::<CardPile>()
+            .init_asset_loader::<CardPileLoader>()
+            .add_system_set(
+                SystemSet::on_enter(GameState::SeasonState).with_system(initialize_cards.system()),
+            )
+            .add_system_set(
+                SystemSet::on_resume(GameState::SeasonState).with_system(initialize_cards.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::SeasonState)
+                    .with_system(next_card.system())
+                    .with_system(move_shape.system())
+                    .with_system(mirror_shape.system())
+                    .with_system(rotate_shape.system())
+                    .with_system(place_shape.system())
+                    .with_system(mouse_position.system())
+                    .with_system(click_card.system()),
+            );
+    }
+}
+
+/// the passing of time and the scoring at the end of each season, the map and the summary are exported at the end of the game
+pub struct SeasonPlugin;
+
+impl Plugin for SeasonPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_event::<SeasonScored>()
+            .add_event::<GameEnded>()
+            .insert_resource(Season::default())
+            .add_system_set(
+                SystemSet::on_enter(GameState::SeasonScoreState).with_system(score_season.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::SeasonScoreState)
+                    .with_system(continue_after_scoring.system()),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::SeasonScoreState)
+                    .with_system(advance_season.system()),
+            )
+            .init_resource::<GameRecord>()
+            .add_system(record_turns.system())
+            .add_system_set(
+                SystemSet::on_enter(GameState::End)
+                    .with_system(export_svg.system())
+                    .with_system(export_summary.system()),
+            );
+    }
+}
+
+/// objectives, coins and the skills they are spent on
+pub struct ObjectivePlugin;
+
+impl Plugin for ObjectivePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        // objectives before skills, so a seed always draws the same of both
+        let (objectives, skills) = {
+            let mut rng = app
+                .world_mut()
+                .get_resource_mut::<GameRng>()
+                .expect("the KarthographPlugin provides the random numbers");
+            (GameObjectives::new(&mut **rng), Skills::new(&mut **rng))
+        };
+
+        app.add_event::<BuySkill>()
+            .insert_resource(objectives)
+            .insert_resource(skills)
+            .add_system_set(
+                SystemSet::on_update(GameState::SeasonState).with_system(buy_skill.system()),
+            );
+    }
+}
+
+/// cameras, sprites of the map and the panels around it
+pub struct UiPlugin;
+
+impl Plugin for UiPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_startup_system(init_camera.system())
+            .add_startup_system(setup_ui.system())
+            .add_system_set(
+                SystemSet::on_exit(GameState::Loading).with_system(spawn_field_sprites.system()),
+            )
+            .add_system_set(
+                SystemSet::on_enter(GameState::SeasonState)
+                    .with_system(setup_objective_ui.system())
+                    .with_system(setup_skill_ui.system())
+                    .with_system(setup_coin_ui.system())
+                    .with_system(setup_season_timeline.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::SeasonState)
+                    .with_system(click_skill.system())
+                    .with_system(highlight_skills.system())
+                    .with_system(update_score_bounds.system()),
+            )
+            .add_system(render_fields.system())
+            .add_system(update_coin_track.system())
+            .add_system(update_score_ui.system())
+            .add_system(update_season_timeline.system())
+            .add_system_set(
+                SystemSet::on_update(GameState::SeasonScoreState)
+                    .with_system(animate_numbers.system()),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::SeasonScoreState)
+                    .with_system(despawn_scoring_screen.system()),
+            );
+    }
+}
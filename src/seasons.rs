@@ -1,10 +1,16 @@
-use bevy::{prelude::*, text::Text};
+#[cfg(feature = "game")]
+use bevy::{log, prelude::*};
+use serde::Serialize;
 
+#[cfg(feature = "game")]
 use crate::asset_management::AssetManager;
+#[cfg(feature = "game")]
 use crate::{
+    events::{GameEnded, SeasonScored},
     grid::Grid,
-    objective::{GameObjectives, SeasonScore},
-    ui::SeasonUiMarker,
+    objective::GameObjectives,
+    players::Players,
+    ui::{spawn_scoring_screen, SeasonUiMarker},
     GameState,
 };
 
@@ -12,16 +18,20 @@ use crate::{
 pub struct Season {
     season_type: SeasonType,
     passed_time: i32,
+    // time of the card, that is currently revealed. It is already part of `passed_time`
+    current_time: i32,
 }
 
 impl Season {
     pub fn pass_time(&mut self, time: i32) {
         self.passed_time += time;
+        self.current_time = time;
     }
 
     pub fn next(&self) -> Option<Self> {
         self.season_type.next().map(|season_type| Self {
             passed_time: 0,
+            current_time: 0,
             season_type,
         })
     }
@@ -30,11 +40,39 @@ impl Season {
         self.passed_time < self.season_type.time()
     }
 
+    pub fn passed_time(&self) -> i32 {
+        self.passed_time
+    }
+
+    pub fn current_time(&self) -> i32 {
+        self.current_time
+    }
+
+    pub fn total_time(&self) -> i32 {
+        self.season_type.time()
+    }
+
+    /// whether a card of the given time would use up the rest of the season
+    pub fn ends_with(&self, time: i32) -> bool {
+        self.passed_time + time >= self.total_time()
+    }
+
     pub fn season_type(&self) -> &SeasonType {
         &self.season_type
     }
+
+    /// time left until `season_type` is scored, `None` if that season is already over
+    pub fn time_until_scoring(&self, season_type: &SeasonType) -> Option<i32> {
+        let mut current = self.season_type;
+        let mut time = self.total_time() - self.passed_time;
+        while current != *season_type {
+            current = current.next()?;
+            time += current.time();
+        }
+        Some(time.max(0))
+    }
 }
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy, Serialize)]
 pub enum SeasonType {
     Spring,
     Summer,
@@ -43,7 +81,14 @@ pub enum SeasonType {
 }
 
 impl SeasonType {
-    fn time(&self) -> i32 {
+    pub const ALL: [SeasonType; 4] = [Self::Spring, Self::Summer, Self::Autumn, Self::Winter];
+
+    /// the time of the longest season
+    pub fn max_time() -> i32 {
+        Self::ALL.iter().map(Self::time).max().unwrap()
+    }
+
+    pub fn time(&self) -> i32 {
         match &self {
             Self::Spring => 8,
             Self::Summer => 8,
@@ -85,47 +130,64 @@ pub enum SeasonMarker {
     Winter,
 }
 
+#[cfg(feature = "game")]
 pub fn score_season(
-    mut state: ResMut<State<GameState>>,
-    mut ui_query: Query<(&mut Text, &SeasonMarker)>,
+    mut com: Commands,
     season: Res<Season>,
     mut objectives: ResMut<GameObjectives>,
     grid: Res<Grid>,
+    mut players: ResMut<Players>,
+    assets: Res<AssetManager>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut scored: EventWriter<SeasonScored>,
 ) {
-    let SeasonScore {
-        a: first,
-        b: second,
-        coin_count,
-    } = objectives.score_season(season.season_type(), &grid);
-
-    // fetch season UI
-    ui_query
-        .iter_mut()
-        .filter(|&(_, marker)| marker == &season.season_type().marker())
-        .for_each(|(mut t, _)| {
-            if t.sections[0].value == first.0 {
-                t.sections[1].value = first.1.to_string();
-            };
-            if t.sections[0].value == second.0 {
-                t.sections[1].value = second.1.to_string();
-            };
-            if t.sections[0].value == "Coins" {
-                t.sections[1].value = coin_count.to_string();
-            }
-        });
+    // the scoring screen shows the first player, the others are scored on their own maps
+    for (player, seat) in players.waiting_mut().enumerate() {
+        let score = seat
+            .objectives
+            .score_season(season.season_type(), &seat.grid);
+        log::info!("player {} scored {} points", player + 1, score.total());
+    }
+    // the score panel picks the new scores up on its own, the scoring screen stays until the player continues
+    let score = objectives.score_season(season.season_type(), &grid);
+    scored.send(SeasonScored {
+        season: *season.season_type(),
+        score: score.clone(),
+    });
+    spawn_scoring_screen(
+        &mut com,
+        &assets,
+        &mut materials,
+        *season.season_type(),
+        score,
+    );
+}
 
-    println!("{:?} scored {:?}", first.0, first.1);
-    println!("{:?} scored {:?}", second.0, second.1);
-    println!("{:?} coins were collected", coin_count);
-    // TODO: coin ui
-    state.pop().unwrap();
+#[cfg(feature = "game")]
+/// Keys close the scoring screen right away, clicks only from the frame after it opened:
+/// the click placing the last shape of the season is still pressed in the frame the screen opens.
+pub fn continue_after_scoring(
+    mut armed: Local<bool>,
+    mut state: ResMut<State<GameState>>,
+    keys: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+) {
+    let clicked = *armed && mouse.just_pressed(MouseButton::Left);
+    *armed = true;
+    if keys.just_pressed(KeyCode::Space) || keys.just_pressed(KeyCode::Return) || clicked {
+        *armed = false;
+        state.pop().unwrap();
+    }
 }
 
+#[cfg(feature = "game")]
 pub fn advance_season(
     assets: Res<AssetManager>,
     mut query: Query<(&SeasonMarker, &mut Handle<ColorMaterial>), With<SeasonUiMarker>>,
     mut season: ResMut<Season>,
     mut state: ResMut<State<GameState>>,
+    objectives: Res<GameObjectives>,
+    mut ended: EventWriter<GameEnded>,
 ) {
     for (marker, mut handle) in query.iter_mut() {
         if *marker == season.season_type().marker() {
@@ -138,7 +200,11 @@ pub fn advance_season(
         *season = next_season;
     } else {
         state.overwrite_set(GameState::End).unwrap();
-        panic!("GAME SHOULD END DON'T YOU THINK?")
+        ended.send(GameEnded {
+            total: objectives.total_score(),
+        });
+        log::info!("game ended with {} points", objectives.total_score());
+        return;
     }
 
     for (marker, mut handle) in query.iter_mut() {
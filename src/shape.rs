@@ -1,18 +1,30 @@
 use derive_deref::*;
 use serde::Deserialize;
 
-use crate::asset_management::{AssetID, AssetManager};
-use crate::card::{Card, RuinIndicator};
-use crate::card_pile::NewCard;
+use crate::card::RuinIndicator;
 use crate::grid::{Coordinate, Cultivation, Grid};
-use crate::objective::GameObjectives;
 use crate::util::min_f;
-use crate::SPRITE_SIZE;
+#[cfg(feature = "game")]
+use crate::{
+    asset_management::{AssetID, AssetManager},
+    card::{spawn_ruin_badge, Card},
+    card_pile::NewCard,
+    events::{CoinGained, ShapePlaced, SkillRefunded},
+    objective::{CoinSource, GameObjectives},
+    players::Players,
+    skill::Skills,
+    SPRITE_SIZE,
+};
+#[cfg(feature = "game")]
 use bevy::input::mouse::{MouseButtonInput, MouseWheel};
+#[cfg(feature = "game")]
 use bevy::{log, prelude::*};
+use bevy_math::{IVec2, Vec2};
+use std::fmt;
+use std::str::FromStr;
 //TODO MIRROR
 
-#[derive(Clone, Deserialize, Deref, DerefMut)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Deref, DerefMut)]
 pub struct Geometry {
     inner: Vec<Coordinate>,
 }
@@ -35,6 +47,43 @@ impl Geometry {
         }
     }
 
+    /// translated, so its smallest x and y are 0, and sorted. Equal shapes have equal normalized geometries
+    pub fn normalized(&self) -> Geometry {
+        let (min_v, _) = self.bounds();
+        let mut inner: Vec<Coordinate> = self
+            .iter()
+            .map(|pos| (pos.inner_copy() - min_v).into())
+            .collect();
+        inner.sort();
+        Self { inner }
+    }
+
+    /// the distinct orientations of the geometry, symmetric geometries have less than eight of them
+    pub fn orientations(&self) -> Vec<(Orientation, Geometry)> {
+        let mut seen = Vec::with_capacity(Orientation::ALL.len());
+        let mut result = Vec::with_capacity(Orientation::ALL.len());
+        for orientation in Orientation::ALL {
+            let oriented = orientation.apply(self);
+            let normalized = oriented.normalized();
+            if !seen.contains(&normalized) {
+                seen.push(normalized);
+                result.push((orientation, oriented));
+            }
+        }
+        result
+    }
+
+    /// the orientations mapping the geometry onto itself, always containing the identity
+    pub fn symmetries(&self) -> Vec<Orientation> {
+        let normalized = self.normalized();
+        Orientation::ALL
+            .iter()
+            .copied()
+            .filter(|orientation| orientation.apply(self).normalized() == normalized)
+            .collect()
+    }
+
+    #[cfg(feature = "game")]
     pub fn as_transforms_centered(&self, distance: f32, z: f32) -> Vec<Transform> {
         let offset = self.center_offset();
         let mut transforms = self.as_transforms(distance, z);
@@ -45,6 +94,7 @@ impl Geometry {
         transforms
     }
 
+    #[cfg(feature = "game")]
     pub fn as_transforms(&self, distance: f32, z: f32) -> Vec<Transform> {
         self.iter()
             .map(|pos| Transform::from_xyz(distance * pos.x as f32, distance * pos.y as f32, z))
@@ -61,6 +111,21 @@ impl Geometry {
         (min_v, max_v)
     }
 
+    // unlike `min_max`, the origin does not have to be part of the bounds
+    /// width and height of the smallest rectangle around the geometry
+    pub fn extent(&self) -> (usize, usize) {
+        let (min_v, max_v) = self.bounds();
+        let size = max_v - min_v + IVec2::ONE;
+        (size.x as usize, size.y as usize)
+    }
+
+    fn bounds(&self) -> (IVec2, IVec2) {
+        let first = self[0].inner_copy();
+        self.iter().fold((first, first), |(min_v, max_v), coord| {
+            (min_v.min(coord.inner_copy()), max_v.max(coord.inner_copy()))
+        })
+    }
+
     pub fn max_size_in_rect(&self, size: Vec2) -> f32 {
         let (min_v, max_v) = self.min_max();
         let diff = max_v - min_v + IVec2::ONE;
@@ -68,6 +133,7 @@ impl Geometry {
         min_f(size.x / (diff.x as f32), size.y / (diff.y as f32))
     }
 
+    #[cfg(feature = "game")]
     fn center_offset(&self) -> Vec3 {
         let (min_v, max_v) = self.min_max();
         let offset = max_v.as_f32() - (max_v.as_f32() - min_v.as_f32()) / 2.;
@@ -75,6 +141,44 @@ impl Geometry {
     }
 }
 
+/// Mirror first (if at all), then rotate clockwise `rotations` times
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Orientation {
+    pub mirrored: bool,
+    pub rotations: u8,
+}
+
+impl Orientation {
+    pub const ALL: [Orientation; 8] = [
+        Orientation::new(false, 0),
+        Orientation::new(false, 1),
+        Orientation::new(false, 2),
+        Orientation::new(false, 3),
+        Orientation::new(true, 0),
+        Orientation::new(true, 1),
+        Orientation::new(true, 2),
+        Orientation::new(true, 3),
+    ];
+
+    pub const fn new(mirrored: bool, rotations: u8) -> Self {
+        Self {
+            mirrored,
+            rotations: rotations % 4,
+        }
+    }
+
+    pub fn apply(&self, geom: &Geometry) -> Geometry {
+        let mut result = geom.clone();
+        if self.mirrored {
+            result.mirror();
+        }
+        for _ in 0..self.rotations {
+            result.rotate_clockwise();
+        }
+        result
+    }
+}
+
 impl Default for Geometry {
     //Geometries are non empty
     fn default() -> Self {
@@ -84,12 +188,88 @@ impl Default for Geometry {
     }
 }
 
+/// Rows of `#` with `.` for gaps, the top row first. The origin is `@`, or `+` if it is not part of the geometry:
+/// ```text
+/// .#
+/// @#
+/// ```
+impl fmt::Display for Geometry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (min_v, max_v) = self.min_max();
+        for y in (min_v.y..=max_v.y).rev() {
+            for x in min_v.x..=max_v.x {
+                let symbol = match (x == 0 && y == 0, self.contains(&(x, y).into())) {
+                    (true, true) => '@',
+                    (true, false) => '+',
+                    (false, true) => '#',
+                    (false, false) => '.',
+                };
+                write!(f, "{}", symbol)?;
+            }
+            if y != min_v.y {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// reads what `Display` writes, without an origin the geometry is normalized
+impl FromStr for Geometry {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows: Vec<&str> = s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        let mut inner = Vec::default();
+        let mut origin = None;
+        for (nth, row) in rows.iter().enumerate() {
+            let y = (rows.len() - 1 - nth) as i32;
+            for (x, symbol) in row.chars().enumerate() {
+                let pos: Coordinate = (x as i32, y).into();
+                match symbol {
+                    '.' => {}
+                    '#' => inner.push(pos),
+                    '@' | '+' => {
+                        if origin.replace(pos).is_some() {
+                            return Err("A geometry has only one origin");
+                        }
+                        if symbol == '@' {
+                            inner.push(pos);
+                        }
+                    }
+                    _ => return Err("Unknown field in geometry"),
+                }
+            }
+        }
+        if inner.is_empty() {
+            return Err("A geometry has at least one field");
+        }
+
+        let geometry = Self { inner };
+        Ok(match origin {
+            Some(origin) => Self {
+                inner: geometry
+                    .iter()
+                    .map(|pos| (pos.inner_copy() - origin.inner_copy()).into())
+                    .collect(),
+            },
+            None => geometry.normalized(),
+        })
+    }
+}
+
 #[derive(Clone)]
 pub struct Shape {
     coin: bool,
     geometry: Geometry,
     cultivation: Cultivation,
     ruin: RuinIndicator,
+    bonus: bool,
+    recultivate: bool,
 }
 
 impl Shape {
@@ -99,6 +279,42 @@ impl Shape {
             cultivation: *cult,
             ruin: *ruin,
             coin,
+            bonus: false,
+            recultivate: false,
+        }
+    }
+
+    /// an additional placement granted by a skill, it never has to be placed on a ruin and never yields a coin
+    pub fn bonus(g: &Geometry, cult: &Cultivation, recultivate: bool) -> Self {
+        Self {
+            bonus: true,
+            recultivate,
+            ..Self::new(g, cult, &false.into(), false)
+        }
+    }
+
+    pub fn has_coin(&self) -> bool {
+        self.coin
+    }
+
+    pub fn is_bonus(&self) -> bool {
+        self.bonus
+    }
+
+    /// whether the shape overwrites cultivated fields instead of free ones
+    pub fn recultivates(&self) -> bool {
+        self.recultivate
+    }
+
+    pub fn ignore_ruin(&mut self) {
+        self.ruin.reset();
+    }
+
+    pub fn fits(&self, grid: &Grid, coord: &Coordinate) -> bool {
+        if self.recultivate {
+            grid.accepts_recultivation_at(&self.geometry, coord)
+        } else {
+            grid.accepts_geometry_at(&self.geometry, coord, &self.ruin)
         }
     }
 
@@ -116,6 +332,7 @@ impl Shape {
         self.ruin
     }
 
+    #[cfg(feature = "game")]
     pub fn spawn(self, com: &mut Commands, assets: &Res<AssetManager>) -> Entity {
         let handle = assets.fetch(self.cultivation.asset_id()).unwrap();
 
@@ -134,6 +351,15 @@ impl Shape {
                 .id();
             children.push(child);
         }
+        if *self.ruin {
+            // sits on the origin of the shape, so rotating and mirroring leave it in place
+            children.push(spawn_ruin_badge(
+                com,
+                assets,
+                Transform::from_xyz(0., 0., 0.1),
+                SPRITE_SIZE / 2.,
+            ));
+        }
         // TODO: spawn with transform??????
         // FIXME: if no new mouse event triggers, transform is not set...
         com.spawn()
@@ -145,6 +371,7 @@ impl Shape {
     }
 
     // POSSIBLE BREAK -> CALLER DETERMINES WHAT TRANSFORMS TO ROTATE
+    #[cfg(feature = "game")]
     pub fn rotate_clockwise(&mut self, transforms: &mut [Mut<Transform>]) {
         for transform in transforms.iter_mut() {
             let x = transform.translation.x;
@@ -156,6 +383,7 @@ impl Shape {
     }
 
     // POSSIBLE BREAK -> CALLER DETERMINES WHAT TRANSFORMS TO ROTATE
+    #[cfg(feature = "game")]
     pub fn rotate_counter_clockwise(&mut self, transforms: &mut [Mut<Transform>]) {
         for transform in transforms.iter_mut() {
             let x = transform.translation.x;
@@ -166,6 +394,7 @@ impl Shape {
         self.geometry.rotate_counter_clockwise();
     }
 
+    #[cfg(feature = "game")]
     pub fn mirror(&mut self, transforms: &mut [Mut<Transform>]) {
         for transform in transforms.iter_mut() {
             transform.translation.x = -transform.translation.x
@@ -184,10 +413,13 @@ impl Default for Shape {
             cultivation: Cultivation::Village,
             ruin: false.into(),
             coin: false,
+            bonus: false,
+            recultivate: false,
         }
     }
 }
 
+#[cfg(feature = "game")]
 pub fn move_shape(
     mut cursor: EventReader<CursorMoved>,
     mut query: Query<(&Shape, &mut Transform)>,
@@ -199,7 +431,7 @@ pub fn move_shape(
             //calculate the closest cell
             let mut position = event.position;
             let grid_pos = Grid::screen_to_grid(position);
-            if grid.accepts_geometry_at(&shape.geometry, &grid_pos, &shape.ruin) {
+            if shape.fits(&grid, &grid_pos) {
                 position = Grid::grid_to_screen(grid_pos);
             }
             // IF CANNOT PLACE => DONT MOVE
@@ -210,6 +442,7 @@ pub fn move_shape(
     }
 }
 
+#[cfg(feature = "game")]
 pub fn rotate_shape(
     mut cursor: EventReader<MouseWheel>,
     mut parents: Query<(Entity, &mut Shape)>,
@@ -232,6 +465,7 @@ pub fn rotate_shape(
     }
 }
 
+#[cfg(feature = "game")]
 pub fn mirror_shape(
     mut clicks: EventReader<MouseButtonInput>,
     mut parents: Query<(Entity, &mut Shape)>,
@@ -250,6 +484,7 @@ pub fn mirror_shape(
     }
 }
 
+#[cfg(feature = "game")]
 #[allow(clippy::too_many_arguments)]
 pub fn place_shape(
     mut com: Commands,
@@ -259,26 +494,49 @@ pub fn place_shape(
     mut clicks: EventReader<MouseButtonInput>,
     mut next_card: EventWriter<NewCard>,
     assets: Res<AssetManager>,
-    mut handles: Query<&mut Handle<ColorMaterial>>,
     mut objectives: ResMut<GameObjectives>,
+    mut skills: ResMut<Skills>,
+    mut placed: EventWriter<ShapePlaced>,
+    mut coins: EventWriter<CoinGained>,
+    mut refunded: EventWriter<SkillRefunded>,
+    mut players: ResMut<Players>,
 ) {
     for event in clicks.iter() {
         if event.button == MouseButton::Left && event.state.is_pressed() {
             if let Ok((t_entity, shape, transform)) = shapes.single() {
                 let position = Vec2::new(transform.translation.x, transform.translation.y);
                 let grid_position = Grid::screen_to_grid(position);
-                if let Ok(coordinates) =
-                    grid.try_cultivate(shape, &grid_position, &assets, &mut handles)
-                {
+                let placement = if shape.recultivates() {
+                    grid.try_recultivate(shape, &grid_position)
+                } else {
+                    grid.try_cultivate(shape, &grid_position)
+                };
+                if let Ok(coordinates) = placement {
                     // the magic happens in try_cultivate, if this is successful, all thats left to do is to despawn the shape and the card
 
+                    objectives.update_scores(&grid, &coordinates);
+                    placed.send(ShapePlaced {
+                        cultivation: shape.cultivation(),
+                        fields: coordinates.clone(),
+                        recultivated: shape.recultivates(),
+                        bonus: shape.is_bonus(),
+                    });
+
                     if shape.coin {
-                        objectives.add_coin(coordinates);
+                        coins.send(CoinGained {
+                            source: CoinSource::Shape,
+                            position: coordinates.clone(),
+                        });
+                        objectives.add_coin(coordinates, CoinSource::Shape);
                         log::info!("coin was added");
                     }
 
                     for coord in grid.mountain_coins() {
-                        objectives.add_coin(vec![coord]);
+                        coins.send(CoinGained {
+                            source: CoinSource::Mountain,
+                            position: vec![coord],
+                        });
+                        objectives.add_coin(vec![coord], CoinSource::Mountain);
                         log::info!("mountain coin was added");
                     }
 
@@ -286,7 +544,27 @@ pub fn place_shape(
                     if let Ok((card_entity, _)) = card.single() {
                         com.entity(card_entity).despawn_recursive();
                     }
-                    next_card.send(NewCard);
+
+                    // a bought skill may grant one more placement, before the next card is drawn
+                    let bonus = if shape.is_bonus() {
+                        Ok(None)
+                    } else {
+                        skills.bonus_shape(shape, &grid, &mut objectives)
+                    };
+                    match bonus {
+                        Ok(Some(bonus)) => {
+                            bonus.spawn(&mut com, &assets);
+                        }
+                        unused => {
+                            if let Err(refund) = unused {
+                                refunded.send(refund);
+                            }
+                            skills.end_turn();
+                            // the next player places the same card on their map, then the next card is drawn
+                            players.pass(&mut grid, &mut objectives);
+                            next_card.send(NewCard);
+                        }
+                    }
                 }
             }
         }
@@ -0,0 +1,250 @@
+#[cfg(feature = "game")]
+use bevy::prelude::*;
+use bevy_utils::tracing as log;
+use derive_deref::*;
+use rand::{prelude::SliceRandom, Rng};
+use serde::Serialize;
+
+#[cfg(feature = "game")]
+use crate::{
+    asset_management::AssetManager,
+    card::{Card, CardClickEvent},
+    events::SkillBought,
+};
+use crate::{
+    card::RuinIndicator,
+    events::SkillRefunded,
+    grid::Grid,
+    objective::GameObjectives,
+    shape::{Geometry, Shape},
+};
+
+const REVEALED_SKILLS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SkillEffect {
+    /// place an additional 1x1 field of the same cultivation
+    ExtraSplinter,
+    /// place the chosen shape a second time
+    ExtraCopy,
+    /// overwrite an already cultivated field with the chosen cultivation
+    Recultivate,
+    /// the current card does not have to be placed on a ruin
+    IgnoreRuin,
+}
+
+#[derive(Debug, Clone)]
+pub struct SkillCard {
+    name: &'static str,
+    cost: usize,
+    effect: SkillEffect,
+}
+
+impl SkillCard {
+    fn new(name: &'static str, cost: usize, effect: SkillEffect) -> Self {
+        Self { name, cost, effect }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn cost(&self) -> usize {
+        self.cost
+    }
+
+    pub fn effect(&self) -> SkillEffect {
+        self.effect
+    }
+}
+
+/// The skill cards revealed for this game and the one bought for the current card, if any.
+/// Skills are paid with coins, which are then no longer counted when scoring a season.
+pub struct Skills {
+    cards: Vec<SkillCard>,
+    active: Option<usize>,
+}
+
+impl Skills {
+    pub fn cards(&self) -> &[SkillCard] {
+        &self.cards
+    }
+
+    pub fn active(&self) -> Option<usize> {
+        self.active
+    }
+
+    fn active_effect(&self) -> Option<SkillEffect> {
+        self.active.map(|idx| self.cards[idx].effect())
+    }
+
+    /// only one skill can be used per card, `ruin` tells whether the card has to be placed on a ruin
+    pub fn buy(
+        &mut self,
+        idx: usize,
+        ruin: &RuinIndicator,
+        objectives: &mut GameObjectives,
+    ) -> Result<SkillEffect, &'static str> {
+        if self.active.is_some() {
+            return Err("A skill was already used for this card");
+        }
+        let card = self.cards.get(idx).ok_or("There is no such skill")?;
+        if card.effect() == SkillEffect::IgnoreRuin && !**ruin {
+            return Err("The card does not have to be placed on a ruin");
+        }
+        objectives.spend_coins(card.cost())?;
+        self.active = Some(idx);
+        Ok(card.effect())
+    }
+
+    pub fn ignores_ruin(&self) -> bool {
+        self.active_effect() == Some(SkillEffect::IgnoreRuin)
+    }
+
+    /// The additional placement granted by the active skill after `placed` has been put on the grid.
+    /// If the grid can't take it anymore, the skill is refunded.
+    pub fn bonus_shape(
+        &mut self,
+        placed: &Shape,
+        grid: &Grid,
+        objectives: &mut GameObjectives,
+    ) -> Result<Option<Shape>, SkillRefunded> {
+        let effect = match self.active_effect() {
+            Some(effect) => effect,
+            None => return Ok(None),
+        };
+        let bonus = match effect {
+            SkillEffect::ExtraSplinter => {
+                Shape::bonus(&Geometry::default(), &placed.cultivation(), false)
+            }
+            SkillEffect::ExtraCopy => Shape::bonus(placed.geometry(), &placed.cultivation(), false),
+            SkillEffect::Recultivate => {
+                Shape::bonus(&Geometry::default(), &placed.cultivation(), true)
+            }
+            SkillEffect::IgnoreRuin => return Ok(None),
+        };
+
+        let placeable = if bonus.recultivates() {
+            grid.all()
+                .any(|field| grid.accepts_recultivation_at(bonus.geometry(), &field.position()))
+        } else {
+            grid.accepts_geometry(bonus.geometry(), &false.into())
+        };
+        if placeable {
+            Ok(Some(bonus))
+        } else {
+            log::info!("bonus shape cannot be placed, skill is refunded");
+            let index = self.active.unwrap();
+            let cost = self.cards[index].cost();
+            objectives.refund_coins(cost);
+            Err(SkillRefunded { index, cost })
+        }
+    }
+
+    pub fn end_turn(&mut self) {
+        self.active = None;
+    }
+}
+
+impl Skills {
+    /// reveals three of the skill cards
+    pub fn new(rng: &mut impl Rng) -> Self {
+        let mut cards = vec![
+            SkillCard::new("Vorposten", 1, SkillEffect::ExtraSplinter),
+            SkillCard::new("Pfadfinder", 1, SkillEffect::IgnoreRuin),
+            SkillCard::new("Umgestaltung", 2, SkillEffect::Recultivate),
+            SkillCard::new("Doppelte Vermessung", 3, SkillEffect::ExtraCopy),
+        ];
+        cards.shuffle(rng);
+        cards.truncate(REVEALED_SKILLS);
+
+        Self {
+            cards,
+            active: None,
+        }
+    }
+}
+
+#[derive(Deref, Clone, Copy)]
+pub struct SkillButton(pub usize);
+
+/// the player wants to buy the revealed skill with this index
+#[derive(Deref, Debug, Clone, Copy)]
+pub struct BuySkill(pub usize);
+
+#[cfg(feature = "game")]
+pub fn click_skill(
+    buttons: Query<(&Interaction, &SkillButton), Changed<Interaction>>,
+    mut requests: EventWriter<BuySkill>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction == Interaction::Clicked {
+            requests.send(BuySkill(**button));
+        }
+    }
+}
+
+#[cfg(feature = "game")]
+#[allow(clippy::too_many_arguments)]
+pub fn buy_skill(
+    mut com: Commands,
+    mut requests: EventReader<BuySkill>,
+    shape: Query<(&Shape, Entity)>,
+    card: Query<&Card>,
+    options: Query<&CardClickEvent>,
+    mut skills: ResMut<Skills>,
+    mut objectives: ResMut<GameObjectives>,
+    mut bought: EventWriter<SkillBought>,
+) {
+    for request in requests.iter() {
+        // skills are bought for the card on the table, not for bonus placements
+        if card.single().is_err() {
+            continue;
+        }
+
+        // the options of the card carry its ruin requirement
+        let ruin: RuinIndicator = options
+            .iter()
+            .any(|option| match option {
+                CardClickEvent::SpawnShape(shape) => *shape.ruin(),
+            })
+            .into();
+        let effect = match skills.buy(**request, &ruin, &mut objectives) {
+            Ok(effect) => effect,
+            Err(reason) => {
+                log::info!("{}", reason);
+                continue;
+            }
+        };
+        log::info!("{:?} was bought", effect);
+        bought.send(SkillBought {
+            index: **request,
+            cost: skills.cards()[**request].cost(),
+            effect,
+        });
+        if effect == SkillEffect::IgnoreRuin {
+            // the shape in hand still requires a ruin, it has to be taken from the card again
+            if let Ok((_, shape_entity)) = shape.single() {
+                com.entity(shape_entity).despawn_recursive();
+            }
+        }
+    }
+}
+
+#[cfg(feature = "game")]
+pub fn highlight_skills(
+    skills: Res<Skills>,
+    assets: Res<AssetManager>,
+    mut buttons: Query<(&SkillButton, &mut Handle<ColorMaterial>)>,
+) {
+    if !skills.is_changed() {
+        return;
+    }
+    for (button, mut handle) in buttons.iter_mut() {
+        *handle = if skills.active() == Some(**button) {
+            assets.ui.highlighted.clone()
+        } else {
+            assets.ui.default.clone()
+        };
+    }
+}
@@ -0,0 +1,267 @@
+use std::fs;
+
+use bevy::{log, prelude::*};
+use serde::Serialize;
+
+use crate::config::GameConfig;
+use crate::events::{CardRevealed, OptionChosen, ShapePlaced, SkillBought, SkillRefunded};
+use crate::grid::{Coordinate, Cultivation, Grid};
+use crate::objective::GameObjectives;
+use crate::players::Players;
+use crate::seasons::SeasonType;
+use crate::skill::SkillEffect;
+
+const SEASONS: [SeasonType; 4] = [
+    SeasonType::Spring,
+    SeasonType::Summer,
+    SeasonType::Autumn,
+    SeasonType::Winter,
+];
+
+fn position(coord: &Coordinate) -> [i32; 2] {
+    [coord.x, coord.y]
+}
+
+/// A revealed card and what was done with it. Cards and geometries are written as text, see `Card::from_str`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Turn {
+    /// the player on turn, every player places the same cards on their own map
+    pub player: usize,
+    pub season: SeasonType,
+    pub passed_time: i32,
+    pub card: String,
+    pub ruin: bool,
+    /// the drawn card, if it could not be placed and got replaced by a splinter
+    pub replaced: Option<String>,
+    /// the option of the card that was placed, the left one is 0
+    pub option: Option<usize>,
+    /// the skill bought for the card
+    pub skill: Option<BoughtSkill>,
+    /// the shapes put onto the grid, bonus shapes of skills included
+    pub placed: Vec<Placed>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Placed {
+    pub cultivation: Cultivation,
+    pub fields: Vec<[i32; 2]>,
+    pub recultivated: bool,
+    pub bonus: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BoughtSkill {
+    /// among the revealed skills
+    pub index: usize,
+    pub cost: usize,
+    pub effect: SkillEffect,
+    /// the skill could not be used and the coins were given back
+    pub refunded: bool,
+}
+
+/// every turn of the game so far, kept for the summary
+#[derive(Debug, Default)]
+pub struct GameRecord {
+    /// cards played before the position the game was loaded from
+    pub skipped: usize,
+    pub turns: Vec<Turn>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MapSummary {
+    pub mountains: Vec<[i32; 2]>,
+    pub ruins: Vec<[i32; 2]>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ObjectiveScore {
+    pub objective: &'static str,
+    pub points: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SeasonSummary {
+    pub season: SeasonType,
+    pub a: ObjectiveScore,
+    pub b: ObjectiveScore,
+    pub coins: usize,
+    pub monster_penalty: usize,
+    pub total: isize,
+}
+
+/// the map and the scores of a player after the first one
+#[derive(Debug, Clone, Serialize)]
+pub struct PlayerSummary {
+    pub grid: Vec<String>,
+    pub seasons: Vec<SeasonSummary>,
+    pub total: isize,
+}
+
+impl PlayerSummary {
+    pub fn new(grid: &Grid, objectives: &GameObjectives) -> Self {
+        Self {
+            grid: grid.to_string().lines().map(str::to_string).collect(),
+            seasons: season_summaries(objectives),
+            total: objectives.total_score(),
+        }
+    }
+}
+
+fn season_summaries(objectives: &GameObjectives) -> Vec<SeasonSummary> {
+    SEASONS
+        .iter()
+        .filter_map(|season| {
+            let score = objectives.season_score(season)?;
+            Some(SeasonSummary {
+                season: *season,
+                a: ObjectiveScore {
+                    objective: score.a.0,
+                    points: score.a.1.points(),
+                },
+                b: ObjectiveScore {
+                    objective: score.b.0,
+                    points: score.b.1.points(),
+                },
+                coins: score.coin_count,
+                monster_penalty: score.monster_penalty,
+                total: score.total(),
+            })
+        })
+        .collect()
+}
+
+/// Everything about a game, meant to be written as JSON at its end.
+/// Seasons not scored yet are left out, the grid is written row by row as in `Grid::from_str`.
+#[derive(Debug, Clone, Serialize)]
+pub struct GameSummary {
+    pub seed: Option<u64>,
+    pub map: MapSummary,
+    /// the objectives A to D, spring scores A and B, summer B and C and so on
+    pub objectives: Vec<&'static str>,
+    /// cards played before the position the game was loaded from, they are not part of `turns`
+    pub skipped_turns: usize,
+    pub turns: Vec<Turn>,
+    pub grid: Vec<String>,
+    pub seasons: Vec<SeasonSummary>,
+    pub total: isize,
+    /// the players after the first one, the map and the scores above are the ones of the first player
+    pub others: Vec<PlayerSummary>,
+}
+
+impl GameSummary {
+    /// `grid` and `objectives` belong to the first player, as they do between the turns
+    pub fn new(
+        config: &GameConfig,
+        record: &GameRecord,
+        grid: &Grid,
+        objectives: &GameObjectives,
+        players: &Players,
+    ) -> Self {
+        Self {
+            seed: config.seed,
+            // the map might come from a board code
+            map: MapSummary {
+                mountains: grid
+                    .mountains()
+                    .map(|field| position(&field.position()))
+                    .collect(),
+                ruins: grid
+                    .ruins()
+                    .map(|field| position(&field.position()))
+                    .collect(),
+            },
+            objectives: objectives
+                .objectives()
+                .map(|objective| objective.name())
+                .collect(),
+            skipped_turns: record.skipped,
+            turns: record.turns.clone(),
+            grid: grid.to_string().lines().map(str::to_string).collect(),
+            seasons: season_summaries(objectives),
+            total: objectives.total_score(),
+            others: players
+                .waiting()
+                .map(|seat| PlayerSummary::new(&seat.grid, &seat.objectives))
+                .collect(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("the summary only holds plain data")
+    }
+}
+
+// a placement and the reveal of the next card can happen in the same frame, a reveal and a choice cannot,
+// so the events are handled in this order
+pub fn record_turns(
+    mut record: ResMut<GameRecord>,
+    mut placed: EventReader<ShapePlaced>,
+    mut revealed: EventReader<CardRevealed>,
+    mut chosen: EventReader<OptionChosen>,
+    mut bought: EventReader<SkillBought>,
+    mut refunded: EventReader<SkillRefunded>,
+) {
+    for event in placed.iter() {
+        if let Some(turn) = record.turns.last_mut() {
+            turn.placed.push(Placed {
+                cultivation: event.cultivation,
+                fields: event.fields.iter().map(position).collect(),
+                recultivated: event.recultivated,
+                bonus: event.bonus,
+            });
+        }
+    }
+    // refunds happen with the placement
+    if refunded.iter().next().is_some() {
+        let skill = record.turns.last_mut().and_then(|turn| turn.skill.as_mut());
+        if let Some(skill) = skill {
+            skill.refunded = true;
+        }
+    }
+    for event in revealed.iter() {
+        record.turns.push(Turn {
+            player: event.player,
+            season: event.season,
+            passed_time: event.passed_time,
+            card: event.card.to_string(),
+            ruin: event.ruin,
+            replaced: event.replaced.as_ref().map(ToString::to_string),
+            option: None,
+            skill: None,
+            placed: Vec::default(),
+        });
+    }
+    // the option can be changed until the shape is placed
+    for event in chosen.iter() {
+        if let Some(turn) = record.turns.last_mut() {
+            turn.option = Some(event.option);
+        }
+    }
+    for event in bought.iter() {
+        if let Some(turn) = record.turns.last_mut() {
+            turn.skill = Some(BoughtSkill {
+                index: event.index,
+                cost: event.cost,
+                effect: event.effect,
+                refunded: false,
+            });
+        }
+    }
+}
+
+/// writes the summary to `GameConfig::summary`, if set
+pub fn export_summary(
+    config: Res<GameConfig>,
+    record: Res<GameRecord>,
+    grid: Res<Grid>,
+    objectives: Res<GameObjectives>,
+    players: Res<Players>,
+) {
+    if let Some(path) = &config.summary {
+        let summary = GameSummary::new(&config, &record, &grid, &objectives, &players);
+        match fs::write(path, summary.to_json()) {
+            Ok(()) => log::info!("summary was written to {}", path),
+            Err(err) => log::warn!("summary could not be written to {}: {}", path, err),
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use std::fmt::{self, Write};
+#[cfg(feature = "game")]
+use std::fs;
+
+#[cfg(feature = "game")]
+use bevy::{log, prelude::*};
+
+#[cfg(feature = "game")]
+use crate::config::GameConfig;
+use crate::grid::{Coordinate, Cultivation, Field, Grid, Terrain};
+use crate::objective::GameObjectives;
+use crate::seasons::SeasonType;
+
+const FIELD: i32 = 40;
+const MARGIN: i32 = 20;
+const LEGEND_WIDTH: i32 = 420;
+const LINE_HEIGHT: i32 = 22;
+const SEASONS: [(SeasonType, &str); 4] = [
+    (SeasonType::Spring, "Spring"),
+    (SeasonType::Summer, "Summer"),
+    (SeasonType::Autumn, "Autumn"),
+    (SeasonType::Winter, "Winter"),
+];
+const OBJECTIVE_LETTERS: [char; 4] = ['A', 'B', 'C', 'D'];
+
+fn cultivation_color(cultivation: Cultivation) -> &'static str {
+    match cultivation {
+        Cultivation::Village => "#c0392b",
+        Cultivation::Water => "#2e86c1",
+        Cultivation::Farm => "#f4d03f",
+        Cultivation::Forest => "#1e8449",
+        Cultivation::Goblin => "#8e44ad",
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// the top left corner of the field, the top row of the grid is drawn first
+fn corner(pos: &Coordinate) -> (i32, i32) {
+    (
+        MARGIN + pos.x * FIELD,
+        MARGIN + (Grid::SIZE as i32 - 1 - pos.y) * FIELD,
+    )
+}
+
+/// The board with its legend as SVG, drawn without any renderer so it works headless as well.
+/// Areas are outlined, ruins are dashed and collected mountain coins are drawn onto their mountain.
+pub fn render(grid: &Grid, objectives: &GameObjectives) -> String {
+    let mut svg = String::default();
+    write_svg(&mut svg, grid, objectives).expect("writing to a string does not fail");
+    svg
+}
+
+fn write_svg(svg: &mut String, grid: &Grid, objectives: &GameObjectives) -> fmt::Result {
+    let board = Grid::SIZE as i32 * FIELD;
+    let width = board + 3 * MARGIN + LEGEND_WIDTH;
+    let height = board + 2 * MARGIN;
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="14">"#,
+        w = width,
+        h = height
+    )?;
+    writeln!(
+        svg,
+        r##"<rect width="{}" height="{}" fill="#ffffff"/>"##,
+        width, height
+    )?;
+
+    for field in grid.all() {
+        write_field(svg, field)?;
+    }
+    write_outlines(svg, grid)?;
+    write_legend(svg, objectives, board + 2 * MARGIN)?;
+
+    writeln!(svg, "</svg>")
+}
+
+fn write_field(svg: &mut String, field: &Field) -> fmt::Result {
+    let (x, y) = corner(&field.position());
+    let fill = match (&field.cultivation, field.terrain()) {
+        (Some(info), _) => cultivation_color(*info.cultivation()),
+        (None, Terrain::Mountain(_)) => "#d5d8dc",
+        (None, _) => "#f5f0e1",
+    };
+    writeln!(
+        svg,
+        r##"<rect x="{}" y="{}" width="{s}" height="{s}" fill="{}" stroke="#999999" stroke-width="0.5"/>"##,
+        x,
+        y,
+        fill,
+        s = FIELD
+    )?;
+
+    match field.terrain() {
+        Terrain::Mountain(coin) => {
+            writeln!(
+                svg,
+                r##"<polygon points="{},{} {},{} {},{}" fill="#707b7c"/>"##,
+                x + FIELD / 2,
+                y + 6,
+                x + FIELD - 5,
+                y + FIELD - 6,
+                x + 5,
+                y + FIELD - 6
+            )?;
+            // collected coins are drawn onto their mountain
+            if !coin {
+                writeln!(
+                    svg,
+                    r##"<circle cx="{}" cy="{}" r="{}" fill="#f1c40f" stroke="#9a7d0a" stroke-width="1.5"/>"##,
+                    x + FIELD / 2,
+                    y + FIELD / 2 + 4,
+                    FIELD / 6
+                )?;
+            }
+        }
+        Terrain::Ruin => writeln!(
+            svg,
+            r##"<rect x="{}" y="{}" width="{s}" height="{s}" fill="none" stroke="#6e2c00" stroke-width="2" stroke-dasharray="4 3"/>"##,
+            x + 4,
+            y + 4,
+            s = FIELD - 8
+        )?,
+        Terrain::Normal => {}
+    }
+    Ok(())
+}
+
+/// a line on every side of a cultivated field, whose neighbor belongs to another area
+fn write_outlines(svg: &mut String, grid: &Grid) -> fmt::Result {
+    let mut path = String::default();
+    for field in grid.all().filter(|field| field.cultivation.is_some()) {
+        let pos = field.position();
+        let area = grid.area_of(&pos);
+        let (x, y) = corner(&pos);
+        // neighbor and the side they share, from one corner to the other
+        let sides = [
+            ((0, 1), (x, y), (x + FIELD, y)),
+            ((0, -1), (x, y + FIELD), (x + FIELD, y + FIELD)),
+            ((-1, 0), (x, y), (x, y + FIELD)),
+            ((1, 0), (x + FIELD, y), (x + FIELD, y + FIELD)),
+        ];
+        for (offset, from, to) in sides.iter() {
+            let neighbor = pos + Coordinate::from(*offset);
+            if grid.area_of(&neighbor) != area {
+                write!(path, "M{} {}L{} {}", from.0, from.1, to.0, to.1)?;
+            }
+        }
+    }
+    writeln!(
+        svg,
+        r##"<path d="{}" fill="none" stroke="#17202a" stroke-width="3" stroke-linecap="square"/>"##,
+        path
+    )
+}
+
+fn write_legend(svg: &mut String, objectives: &GameObjectives, left: i32) -> fmt::Result {
+    let mut y = MARGIN + LINE_HEIGHT / 2;
+    let mut line = |svg: &mut String, text: &str, bold: bool| {
+        let weight = if bold { r#" font-weight="bold""# } else { "" };
+        let result = writeln!(
+            svg,
+            r#"<text x="{}" y="{}"{}>{}</text>"#,
+            left,
+            y,
+            weight,
+            escape(text)
+        );
+        y += LINE_HEIGHT;
+        result
+    };
+
+    line(svg, "Objectives", true)?;
+    for (letter, objective) in OBJECTIVE_LETTERS.iter().zip(objectives.objectives()) {
+        line(svg, &format!("{}  {}", letter, objective.name()), false)?;
+    }
+    line(svg, "", false)?;
+
+    line(svg, "Seasons", true)?;
+    for (idx, (season, name)) in SEASONS.iter().enumerate() {
+        let letters = format!(
+            "{}{}",
+            OBJECTIVE_LETTERS[idx],
+            OBJECTIVE_LETTERS[(idx + 1) % 4]
+        );
+        let text = match objectives.season_score(season) {
+            Some(score) => format!(
+                "{} ({}): {} + {} + {} coins - {} monsters = {}",
+                name,
+                letters,
+                score.a.1.points(),
+                score.b.1.points(),
+                score.coin_count,
+                score.monster_penalty,
+                score.total()
+            ),
+            None => format!("{} ({}): not scored yet", name, letters),
+        };
+        line(svg, &text, false)?;
+    }
+    line(svg, "", false)?;
+
+    line(
+        svg,
+        &format!("Total: {} points", objectives.total_score()),
+        true,
+    )?;
+    line(svg, &format!("Coins: {}", objectives.coins()), false)?;
+    Ok(())
+}
+
+#[cfg(feature = "game")]
+/// writes the final map to `GameConfig::svg`, if set
+pub fn export_svg(config: Res<GameConfig>, grid: Res<Grid>, objectives: Res<GameObjectives>) {
+    if let Some(path) = &config.svg {
+        match fs::write(path, render(&grid, &objectives)) {
+            Ok(()) => log::info!("map was written to {}", path),
+            Err(err) => log::warn!("map could not be written to {}: {}", path, err),
+        }
+    }
+}
@@ -1,6 +1,20 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, render::camera::WindowOrigin};
+use std::cmp::min;
 
-use crate::{asset_management::AssetManager, objective::GameObjectives, seasons::SeasonType};
+use crate::{
+    asset_management::AssetManager,
+    card::Card,
+    grid::Grid,
+    objective::{estimated_fields_in_time, CoinSource, GameObjectives, SeasonScore},
+    seasons::{Season, SeasonType},
+    skill::{SkillButton, Skills},
+};
+
+pub fn init_camera(mut com: Commands) {
+    let mut bundle = OrthographicCameraBundle::new_2d();
+    bundle.orthographic_projection.window_origin = WindowOrigin::BottomLeft;
+    com.spawn_bundle(bundle);
+}
 
 pub fn setup_ui(mut com: Commands) {
     com.spawn_bundle(UiCameraBundle::default());
@@ -35,6 +49,52 @@ pub fn setup_objective_ui(
 
 pub struct SeasonUiMarker;
 
+#[derive(Clone, Copy)]
+pub enum SeasonScoreKind {
+    A,
+    B,
+    Coins,
+    Total,
+}
+
+/// marks texts, whose second section shows a score
+#[derive(Clone, Copy)]
+pub enum ScoreText {
+    Season(SeasonType, SeasonScoreKind),
+    Total,
+    Coins,
+}
+
+/// one slot of the coin track, filled once at least `self.0 + 1` coins are available
+pub struct CoinSlot(usize);
+
+const COIN_TRACK_LENGTH: usize = 14;
+
+/// the segments of the season timeline, their width is the share of the season's time
+pub enum TimelineSegment {
+    Elapsed,
+    CurrentCard,
+}
+
+pub struct TimelineText;
+
+/// root of the interstitial screen shown while a season is scored
+pub struct ScoringScreen;
+
+const SCORE_ANIMATION_SECONDS: f32 = 1.0;
+
+/// counts the second text section up (or down) to `target`
+pub struct AnimatedNumber {
+    target: isize,
+    shown: f32,
+}
+
+impl AnimatedNumber {
+    fn new(target: isize) -> Self {
+        Self { target, shown: 0. }
+    }
+}
+
 fn setup_season_ui(
     child_builder: &mut ChildBuilder,
     season: SeasonType,
@@ -106,6 +166,23 @@ fn setup_season_ui(
                     value: "Coins".to_string(),
                     style: text_style.clone(),
                 },
+                TextSection {
+                    value: "".to_string(),
+                    style: text_style.clone(),
+                },
+            ],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let total_child = TextBundle {
+        text: Text {
+            sections: vec![
+                TextSection {
+                    value: "Total".to_string(),
+                    style: text_style.clone(),
+                },
                 TextSection {
                     value: "".to_string(),
                     style: text_style,
@@ -132,11 +209,420 @@ fn setup_season_ui(
             ..Default::default()
         })
         .with_children(|parent| {
-            parent.spawn_bundle(coin_child).insert(marker);
-            parent.spawn_bundle(second_objective).insert(marker);
-            parent.spawn_bundle(first_objective).insert(marker);
+            parent
+                .spawn_bundle(total_child)
+                .insert(ScoreText::Season(season, SeasonScoreKind::Total));
+            parent
+                .spawn_bundle(coin_child)
+                .insert(ScoreText::Season(season, SeasonScoreKind::Coins));
+            parent
+                .spawn_bundle(second_objective)
+                .insert(ScoreText::Season(season, SeasonScoreKind::B));
+            parent
+                .spawn_bundle(first_objective)
+                .insert(ScoreText::Season(season, SeasonScoreKind::A));
             parent.spawn_bundle(season_name);
         })
         .insert(marker)
         .insert(SeasonUiMarker);
 }
+
+pub fn setup_skill_ui(mut com: Commands, skills: Res<Skills>, assets: Res<AssetManager>) {
+    let text_style = TextStyle {
+        font: assets.font.clone(),
+        font_size: 30.0,
+        color: Color::BLACK,
+    };
+
+    com.spawn_bundle(NodeBundle {
+        style: Style {
+            // right above the card, so it neither covers the grid nor the card
+            size: Size::new(Val::Px(300.0), Val::Px(200.0)),
+            position_type: PositionType::Absolute,
+            position: Rect {
+                left: Val::Px(925.0),
+                bottom: Val::Px(560.0),
+                ..Default::default()
+            },
+            flex_direction: FlexDirection::Column,
+            ..Default::default()
+        },
+        material: assets.ui.default.clone(),
+        ..Default::default()
+    })
+    .with_children(|parent| {
+        for (idx, skill) in skills.cards().iter().enumerate() {
+            parent
+                .spawn_bundle(ButtonBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(98.0), Val::Percent(30.0)),
+                        margin: Rect::all(Val::Percent(1.0)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    },
+                    material: assets.ui.default.clone(),
+                    ..Default::default()
+                })
+                .with_children(|button| {
+                    button.spawn_bundle(TextBundle {
+                        text: Text::with_section(
+                            format!("{} ({})", skill.name(), skill.cost()),
+                            text_style.clone(),
+                            Default::default(),
+                        ),
+                        ..Default::default()
+                    });
+                })
+                .insert(SkillButton(idx));
+        }
+    });
+}
+
+pub fn setup_coin_ui(mut com: Commands, assets: Res<AssetManager>) {
+    let text_style = TextStyle {
+        font: assets.font.clone(),
+        font_size: 30.0,
+        color: Color::BLACK,
+    };
+    let score_text = |label: &str| TextBundle {
+        text: Text {
+            sections: vec![
+                TextSection {
+                    value: label.to_string(),
+                    style: text_style.clone(),
+                },
+                TextSection {
+                    value: "".to_string(),
+                    style: text_style.clone(),
+                },
+            ],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let total_text = score_text("Total: ");
+    let coin_text = score_text("Coins: ");
+
+    // right above the skills
+    com.spawn_bundle(NodeBundle {
+        style: Style {
+            size: Size::new(Val::Px(300.0), Val::Px(160.0)),
+            position_type: PositionType::Absolute,
+            position: Rect {
+                left: Val::Px(925.0),
+                bottom: Val::Px(770.0),
+                ..Default::default()
+            },
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        },
+        material: assets.ui.default.clone(),
+        ..Default::default()
+    })
+    .with_children(|parent| {
+        parent
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(100.0), Val::Px(80.0)),
+                    flex_wrap: FlexWrap::Wrap,
+                    justify_content: JustifyContent::Center,
+                    ..Default::default()
+                },
+                material: assets.ui.default.clone(),
+                ..Default::default()
+            })
+            .with_children(|track| {
+                for idx in 0..COIN_TRACK_LENGTH {
+                    track
+                        .spawn_bundle(ImageBundle {
+                            style: Style {
+                                size: Size::new(Val::Px(36.0), Val::Px(36.0)),
+                                margin: Rect::all(Val::Px(2.0)),
+                                ..Default::default()
+                            },
+                            material: assets.fetch("default").unwrap(),
+                            ..Default::default()
+                        })
+                        .insert(CoinSlot(idx));
+                }
+            });
+        parent.spawn_bundle(coin_text).insert(ScoreText::Coins);
+        parent.spawn_bundle(total_text).insert(ScoreText::Total);
+    });
+}
+
+pub fn update_coin_track(
+    objectives: Res<GameObjectives>,
+    assets: Res<AssetManager>,
+    mut slots: Query<(&CoinSlot, &mut Handle<ColorMaterial>)>,
+) {
+    if !objectives.is_changed() {
+        return;
+    }
+    for (CoinSlot(idx), mut handle) in slots.iter_mut() {
+        let asset = if *idx < objectives.coins() {
+            "coin"
+        } else {
+            "default"
+        };
+        *handle = assets.fetch(asset).unwrap();
+    }
+}
+
+pub fn update_score_ui(objectives: Res<GameObjectives>, mut texts: Query<(&mut Text, &ScoreText)>) {
+    if !objectives.is_changed() {
+        return;
+    }
+    for (mut text, score_text) in texts.iter_mut() {
+        let value = match score_text {
+            ScoreText::Season(season, kind) => match objectives.season_score(season) {
+                Some(score) => match kind {
+                    SeasonScoreKind::A => score.a.1.to_string(),
+                    SeasonScoreKind::B => score.b.1.to_string(),
+                    SeasonScoreKind::Coins => score.coin_count.to_string(),
+                    SeasonScoreKind::Total => score.total().to_string(),
+                },
+                None => continue,
+            },
+            ScoreText::Total => objectives.total_score().to_string(),
+            ScoreText::Coins => format!(
+                "{} ({} from shapes, {} from mountains)",
+                objectives.coins(),
+                objectives.coins_from(CoinSource::Shape),
+                objectives.coins_from(CoinSource::Mountain)
+            ),
+        };
+        text.sections[1].value = value;
+    }
+}
+
+/// shows the most points the objectives of seasons, that have not been scored yet, could still yield
+pub fn update_score_bounds(
+    objectives: Res<GameObjectives>,
+    grid: Res<Grid>,
+    season: Res<Season>,
+    mut texts: Query<(&mut Text, &ScoreText)>,
+    added: Query<&ScoreText, Added<ScoreText>>,
+) {
+    if !grid.is_changed() && !season.is_changed() && added.iter().next().is_none() {
+        return;
+    }
+    for (mut text, score_text) in texts.iter_mut() {
+        let (season_type, kind) = match score_text {
+            ScoreText::Season(season_type, kind) => (season_type, kind),
+            _ => continue,
+        };
+        if objectives.season_score(season_type).is_some() {
+            continue;
+        }
+        let fields_left = match season.time_until_scoring(season_type) {
+            Some(time) => estimated_fields_in_time(time),
+            None => continue,
+        };
+
+        let (a, b) = objectives.upper_bounds(season_type, &grid, fields_left);
+        let bound = match kind {
+            SeasonScoreKind::A => a,
+            SeasonScoreKind::B => b,
+            _ => continue,
+        };
+        text.sections[1].value = format!(" (about {})", bound.points());
+    }
+}
+
+pub fn setup_season_timeline(
+    mut com: Commands,
+    assets: Res<AssetManager>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let text_style = TextStyle {
+        font: assets.font.clone(),
+        font_size: 30.0,
+        color: Color::BLACK,
+    };
+
+    // right above the grid
+    com.spawn_bundle(NodeBundle {
+        style: Style {
+            size: Size::new(Val::Px(825.0), Val::Px(40.0)),
+            position_type: PositionType::Absolute,
+            position: Rect {
+                left: Val::Px(37.5),
+                bottom: Val::Px(880.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        material: materials.add(Color::rgba(0.65, 0.65, 0.65, 0.5).into()),
+        ..Default::default()
+    })
+    .with_children(|parent| {
+        parent
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(0.0), Val::Percent(100.0)),
+                    ..Default::default()
+                },
+                material: assets.ui.default.clone(),
+                ..Default::default()
+            })
+            .insert(TimelineSegment::Elapsed);
+        parent
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(0.0), Val::Percent(100.0)),
+                    ..Default::default()
+                },
+                material: assets.ui.highlighted.clone(),
+                ..Default::default()
+            })
+            .insert(TimelineSegment::CurrentCard);
+        parent
+            .spawn_bundle(TextBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: Rect {
+                        left: Val::Px(10.0),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                text: Text::with_section("", text_style, Default::default()),
+                ..Default::default()
+            })
+            .insert(TimelineText);
+    });
+}
+
+pub fn update_season_timeline(
+    season: Res<Season>,
+    mut segments: Query<(&mut Style, &TimelineSegment)>,
+    mut texts: Query<&mut Text, With<TimelineText>>,
+) {
+    if !season.is_changed() {
+        return;
+    }
+    let share = |time: i32| Val::Percent(100. * time as f32 / season.total_time() as f32);
+    for (mut style, segment) in segments.iter_mut() {
+        style.size.width = match segment {
+            TimelineSegment::Elapsed => share(season.passed_time() - season.current_time()),
+            // the current card may overshoot the season
+            TimelineSegment::CurrentCard => share(min(
+                season.current_time(),
+                season.total_time() - season.passed_time() + season.current_time(),
+            )),
+        };
+    }
+
+    let mut description = format!(
+        "{:?}: {}/{} (current card: {})",
+        season.season_type(),
+        season.passed_time(),
+        season.total_time(),
+        season.current_time()
+    );
+    if !season.has_time_left() {
+        description.push_str(" - season ends with this card");
+    } else if season.ends_with(Card::MAX_TIME) {
+        description.push_str(" - the next card may end the season");
+    }
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = description.clone();
+    }
+}
+
+pub fn spawn_scoring_screen(
+    com: &mut Commands,
+    assets: &AssetManager,
+    materials: &mut Assets<ColorMaterial>,
+    season: SeasonType,
+    score: &SeasonScore,
+) {
+    let text_style = TextStyle {
+        font: assets.font.clone(),
+        font_size: 60.0,
+        color: Color::WHITE,
+    };
+    let line = |label: String, value: Option<isize>| {
+        let bundle = TextBundle {
+            text: Text {
+                sections: vec![
+                    TextSection {
+                        value: label,
+                        style: text_style.clone(),
+                    },
+                    TextSection {
+                        value: "".to_string(),
+                        style: text_style.clone(),
+                    },
+                ],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        (bundle, value.map(AnimatedNumber::new))
+    };
+
+    // bevy ui stacks columns from the bottom up
+    let lines = vec![
+        line("Click or press space to continue".to_string(), None),
+        line("Season total: ".to_string(), Some(score.total())),
+        line(
+            "Monsters: -".to_string(),
+            Some(score.monster_penalty as isize),
+        ),
+        line("Coins: ".to_string(), Some(score.coin_count as isize)),
+        line(
+            format!("{}: ", score.b.0),
+            Some(score.b.1.points() as isize),
+        ),
+        line(
+            format!("{}: ", score.a.0),
+            Some(score.a.1.points() as isize),
+        ),
+        line(format!("{:?}", season), None),
+    ];
+
+    com.spawn_bundle(NodeBundle {
+        style: Style {
+            size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+            position_type: PositionType::Absolute,
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        },
+        material: materials.add(Color::rgba(0.1, 0.1, 0.1, 0.85).into()),
+        ..Default::default()
+    })
+    .with_children(|parent| {
+        for (bundle, number) in lines {
+            let mut entity = parent.spawn_bundle(bundle);
+            if let Some(number) = number {
+                entity.insert(number);
+            }
+        }
+    })
+    .insert(ScoringScreen);
+}
+
+pub fn animate_numbers(time: Res<Time>, mut numbers: Query<(&mut Text, &mut AnimatedNumber)>) {
+    for (mut text, mut number) in numbers.iter_mut() {
+        let target = number.target as f32;
+        let step = target.abs().max(1.) * time.delta_seconds() / SCORE_ANIMATION_SECONDS;
+        number.shown = if (target - number.shown).abs() <= step {
+            target
+        } else {
+            number.shown + step * (target - number.shown).signum()
+        };
+        text.sections[1].value = (number.shown.round() as isize).to_string();
+    }
+}
+
+pub fn despawn_scoring_screen(mut com: Commands, screens: Query<Entity, With<ScoringScreen>>) {
+    for screen in screens.iter() {
+        com.entity(screen).despawn_recursive();
+    }
+}
@@ -1,6 +1,6 @@
 use std::convert::TryInto;
 
-use bevy::math::Vec2;
+use bevy_math::Vec2;
 
 //https://stackoverflow.com/a/29570662/5862030
 pub fn to_array<T, const N: usize>(v: Vec<T>) -> [T; N] {
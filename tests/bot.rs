@@ -0,0 +1,128 @@
+use std::collections::VecDeque;
+
+use karthograph::{
+    bot::{multi_line, Connection, Move, Referee, PROTOCOL},
+    config::GameConfig,
+    grid::Grid,
+    headless::HeadlessGame,
+    shape::Geometry,
+};
+
+/// answers with the first legal placement, after trying an illegal one on its first card
+#[derive(Default)]
+struct FirstFit {
+    replies: VecDeque<String>,
+    board: Option<Grid>,
+    ruin: bool,
+    options: Vec<Geometry>,
+    tried_illegal: bool,
+    illegal: usize,
+    scored: usize,
+    end: Option<isize>,
+}
+
+impl FirstFit {
+    fn first_fit(&self) -> Move {
+        let grid = self.board.as_ref().unwrap();
+        self.options
+            .iter()
+            .enumerate()
+            .find_map(|(option, geometry)| {
+                let placement = *grid.legal_placements(geometry, &self.ruin.into()).first()?;
+                Some(Move { option, placement })
+            })
+            .unwrap()
+    }
+}
+
+impl Connection for FirstFit {
+    fn send(&mut self, line: &str) -> Result<(), &'static str> {
+        let (command, rest) = line.split_once(' ').unwrap_or((line, ""));
+        match command {
+            _ if line == PROTOCOL => {
+                self.replies.push_back("bot first fit".to_string());
+                self.replies.push_back("ready".to_string());
+            }
+            "board" => self.board = Some(multi_line(rest).parse().unwrap()),
+            "card" => {
+                self.ruin = rest.ends_with('1');
+                self.options.clear();
+            }
+            "option" => {
+                let geometry = rest.split(' ').nth(3).unwrap();
+                self.options.push(multi_line(geometry).parse().unwrap());
+            }
+            "go" if !self.tried_illegal => {
+                self.tried_illegal = true;
+                // outside of the map
+                self.replies.push_back("move 0 0 0 -3 20".to_string());
+            }
+            "go" => self.replies.push_back(self.first_fit().to_string()),
+            "illegal" => {
+                self.illegal += 1;
+                self.replies.push_back(self.first_fit().to_string());
+            }
+            "scored" => self.scored += 1,
+            "end" => self.end = Some(rest.parse().unwrap()),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn receive(&mut self) -> Result<String, &'static str> {
+        self.replies.pop_front().ok_or("The bot has nothing to say")
+    }
+}
+
+fn referee<C: Connection>(seed: u64, bot: C) -> Referee<C> {
+    let mut game = HeadlessGame::new(GameConfig {
+        seed: Some(seed),
+        ..Default::default()
+    });
+    game.load().unwrap();
+    Referee { game, bot }
+}
+
+#[test]
+fn a_bot_plays_a_whole_game() {
+    let mut referee = referee(8, FirstFit::default());
+
+    let total = referee.run().unwrap();
+
+    assert_eq!(referee.bot.end, Some(total));
+    assert_eq!(referee.bot.scored, 4);
+    assert_eq!(referee.bot.illegal, 1);
+}
+
+#[test]
+fn moves_are_read_as_they_are_written() {
+    for line in ["move 1 0 3 4 5", "move 0 1 0 -1 10"] {
+        assert_eq!(line.parse::<Move>().unwrap().to_string(), line);
+    }
+    assert!("move 1 2 3 4 5".parse::<Move>().is_err());
+    assert!("move 1 0 3 4".parse::<Move>().is_err());
+}
+
+/// always answers the same
+struct Stubborn(&'static str);
+
+impl Connection for Stubborn {
+    fn send(&mut self, _: &str) -> Result<(), &'static str> {
+        Ok(())
+    }
+
+    fn receive(&mut self) -> Result<String, &'static str> {
+        Ok(self.0.to_string())
+    }
+}
+
+#[test]
+fn illegal_moves_lose_the_game() {
+    let mut referee = referee(9, Stubborn("ready"));
+    assert!(referee.run().is_err());
+    assert!(referee
+        .game
+        .grid()
+        .all()
+        .all(|field| field.cultivation.is_none()));
+}
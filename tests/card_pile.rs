@@ -0,0 +1,58 @@
+use karthograph::{
+    card::{Card, RuinIndicator},
+    card_pile::{replace_unplacable, CardPile},
+    grid::Grid,
+};
+
+const SHAPE: &str = "shape f\n@#\n\n@##";
+
+/// the pile with the first card on top
+fn pile(cards: &[&str]) -> CardPile {
+    CardPile {
+        cards: cards
+            .iter()
+            .rev()
+            .map(|card| card.parse().unwrap())
+            .collect(),
+    }
+}
+
+#[test]
+fn ruins_in_a_row_mark_the_next_card_once() {
+    let mut pile = pile(&["ruin", "ruin", SHAPE, "splinter"]);
+    let mut ruin = RuinIndicator::default();
+
+    assert_eq!(pile.draw(&mut ruin).unwrap().to_string(), SHAPE);
+    assert!(*ruin);
+    // the game resets it, once the card is on the table
+    ruin.reset();
+    assert_eq!(pile.draw(&mut ruin).unwrap().to_string(), "splinter");
+    assert!(!*ruin);
+    assert!(pile.draw(&mut ruin).is_none());
+}
+
+#[test]
+fn a_ruin_at_the_bottom_of_the_pile_marks_nothing() {
+    let mut pile = pile(&[SHAPE, "ruin"]);
+    let mut ruin = RuinIndicator::default();
+
+    assert!(pile.draw(&mut ruin).is_some());
+    assert!(pile.draw(&mut ruin).is_none());
+    assert!(!*ruin);
+}
+
+#[test]
+fn cards_without_a_free_ruin_become_a_splinter() {
+    let mut ruin = RuinIndicator::from(true);
+    let mut card: Card = SHAPE.parse().unwrap();
+    assert!(replace_unplacable(&mut card, &Grid::new(), &mut ruin).is_none());
+    assert_eq!(card.to_string(), SHAPE);
+    assert!(*ruin);
+
+    // goblins took every ruin
+    let grid: Grid = Grid::new().to_string().replace('R', "G").parse().unwrap();
+    let replaced = replace_unplacable(&mut card, &grid, &mut ruin);
+    assert_eq!(replaced.unwrap().to_string(), SHAPE);
+    assert_eq!(card.to_string(), "splinter");
+    assert!(!*ruin);
+}
@@ -0,0 +1,95 @@
+use karthograph::{config::GameConfig, env::Env};
+
+#[test]
+fn an_agent_plays_a_whole_game() {
+    let mut env = Env::new(GameConfig::default());
+    let observation = env.reset(10).unwrap();
+    assert_eq!(observation.len(), Env::OBSERVATION_SIZE);
+
+    let mut rewards = 0.;
+    for _ in 0..100 {
+        let mask = env.action_mask();
+        assert_eq!(mask.len(), Env::ACTION_COUNT);
+        // the last legal action, so other orientations than the first are played as well
+        let action = mask.iter().rposition(|&legal| legal).unwrap();
+
+        let transition = env.step(action).unwrap();
+        assert_eq!(transition.observation.len(), Env::OBSERVATION_SIZE);
+        assert!(transition
+            .observation
+            .iter()
+            .all(|value| (0. ..=1.).contains(value)));
+        rewards += transition.reward;
+        if transition.done {
+            break;
+        }
+    }
+
+    let total = env.game().unwrap().objectives().total_score();
+    assert_eq!(rewards, total as f32);
+    assert!(env.action_mask().iter().all(|&legal| !legal));
+    assert!(env.step(0).is_err());
+}
+
+#[test]
+fn illegal_actions_change_nothing() {
+    let mut env = Env::new(GameConfig::default());
+    let observation = env.reset(11).unwrap();
+    let mask = env.action_mask();
+    let illegal = mask.iter().position(|&legal| !legal).unwrap();
+
+    assert!(env.step(illegal).is_err());
+    assert!(env.step(Env::ACTION_COUNT).is_err());
+    assert_eq!(env.observation(), observation);
+}
+
+#[test]
+fn actions_are_decoded_as_they_are_encoded() {
+    for action in [0, 1, 120, 121, 967, Env::ACTION_COUNT - 1] {
+        assert_eq!(Env::encode(&Env::decode(action).unwrap()), action);
+    }
+}
+
+#[test]
+fn every_placement_is_one_legal_action() {
+    let mut env = Env::new(GameConfig::default());
+    env.reset(12).unwrap();
+    let legal = env.action_mask().iter().filter(|&&legal| legal).count();
+
+    let game = env.game().unwrap();
+    let placements: usize = game
+        .options()
+        .iter()
+        .map(|shape| {
+            game.grid()
+                .legal_placements(shape.geometry(), &shape.ruin())
+                .len()
+        })
+        .sum();
+    assert_eq!(legal, placements);
+}
+
+#[test]
+fn observations_stay_between_zero_and_one() {
+    let mut env = Env::new(GameConfig::default());
+    // the last card of a season often takes more time than is left
+    for seed in 0..6 {
+        env.reset(seed).unwrap();
+        loop {
+            let action = env.action_mask().iter().position(|&legal| legal).unwrap();
+            let transition = env.step(action).unwrap();
+            for (idx, value) in transition.observation.iter().enumerate() {
+                assert!(
+                    (0. ..=1.).contains(value),
+                    "seed {}: {} at {}",
+                    seed,
+                    value,
+                    idx
+                );
+            }
+            if transition.done {
+                break;
+            }
+        }
+    }
+}
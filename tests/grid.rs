@@ -0,0 +1,292 @@
+use itertools::Itertools;
+use std::{fs, path::Path};
+
+use karthograph::{
+    analytics::{Diagonal, Occupancy, Rect},
+    area::Areas,
+    card::RuinIndicator,
+    card_pile::CardPile,
+    fingerprint::transform,
+    grid::{Coordinate, Cultivation, Grid},
+    shape::{Geometry, Orientation, Shape},
+};
+
+const BOARD: &str = "
+    ...........
+    ...M.....R.
+    .R.vv......
+    ...vV...M..
+    .......ww..
+    .....m.wW..
+    .ff........
+    .ffF...aa..
+    ........gg.
+    ..M....R...
+    ...........
+";
+
+// every symmetry of the board keeps its terrain
+const SYMMETRIC_BOARD: &str = "
+    ...........
+    .R.......R.
+    ...........
+    ...........
+    ...........
+    .....M.....
+    ...........
+    ...........
+    ...........
+    .R.......R.
+    ...........
+";
+
+fn shape(geometry: &str, cultivation: Cultivation) -> Shape {
+    let geometry: Geometry = geometry.parse().unwrap();
+    Shape::new(&geometry, &cultivation, &RuinIndicator::default(), false)
+}
+
+#[test]
+fn grids_outside_of_the_game_do_not_record_changes() {
+    let mut grid: Grid = Grid::new().to_string().parse().unwrap();
+    assert!(!grid.has_changes());
+    grid.try_cultivate(&shape("@#", Cultivation::Forest), &(0, 0).into())
+        .unwrap();
+    assert!(!grid.has_changes());
+}
+
+#[test]
+fn tracked_grids_record_their_fields_and_changes() {
+    let mut grid = Grid::new();
+    grid.track_changes();
+    let mountains = grid.mountains().count();
+    assert_eq!(
+        grid.drain_changes().count(),
+        mountains + grid.ruins().count()
+    );
+
+    grid.try_cultivate(&shape("@#", Cultivation::Forest), &(0, 0).into())
+        .unwrap();
+    assert_eq!(grid.drain_changes().count(), 2);
+}
+
+fn sorted<'a>(coords: impl Iterator<Item = &'a Coordinate>) -> Vec<(i32, i32)> {
+    coords.map(|coord| (coord.x, coord.y)).sorted().collect()
+}
+
+#[test]
+fn areas_are_merged_by_the_field_connecting_them() {
+    let mut areas = Areas::default();
+    let left = areas.insert(&(0, 0).into(), Cultivation::Forest);
+    let right = areas.insert(&(2, 0).into(), Cultivation::Forest);
+    let village = areas.insert(&(3, 0).into(), Cultivation::Village);
+    assert_ne!(areas.find(left), areas.find(right));
+
+    let merged = areas.insert(&(1, 0).into(), Cultivation::Forest);
+    assert_eq!(areas.find(left), Some(merged));
+    assert_eq!(areas.find(right), Some(merged));
+    let info = areas.info(&left).unwrap();
+    assert_eq!(info.size(), 3);
+    // the village is next to the forest as well
+    assert_eq!(
+        sorted(info.boundary().iter()),
+        vec![(0, 1), (1, 1), (2, 1), (3, 0)]
+    );
+    assert_eq!(areas.neighbor_areas(&merged), vec![village]);
+    assert_eq!(
+        sorted(areas.info(&village).unwrap().boundary().iter()),
+        vec![(2, 0), (3, 1), (4, 0)]
+    );
+}
+
+#[test]
+fn parsed_boards_know_their_areas() {
+    let grid: Grid = BOARD.parse().unwrap();
+    let village = grid.area_of(&(3, 7).into()).unwrap();
+    assert_eq!(grid.area_of(&(4, 8).into()), Some(village));
+    assert_eq!(grid.area(&village).unwrap().size(), 4);
+    assert_eq!(grid.area_neighbors(&village).count(), 8);
+}
+
+fn card_geometries() -> Vec<Geometry> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/content.cardpile");
+    let pile: CardPile = fs::read_to_string(path).unwrap().parse().unwrap();
+    pile.cards
+        .iter()
+        .flat_map(|card| card.options(&RuinIndicator::default()))
+        .map(|shape| shape.geometry().clone())
+        .collect()
+}
+
+#[test]
+fn bitboards_agree_with_checking_every_anchor() {
+    let full: Grid = BOARD.replace('.', "f").parse().unwrap();
+    for grid in [Grid::new(), BOARD.parse().unwrap(), full] {
+        for geometry in card_geometries() {
+            for orientation in Orientation::ALL {
+                let oriented = orientation.apply(&geometry);
+                for ruin in [false, true] {
+                    let ruin = RuinIndicator::from(ruin);
+                    let anchors = grid.legal_anchors(&oriented, &ruin);
+                    // anchors next to the grid are checked as well, they are never legal
+                    for x in -5..Grid::SIZE as i32 + 5 {
+                        for y in -5..Grid::SIZE as i32 + 5 {
+                            let anchor: Coordinate = (x, y).into();
+                            assert_eq!(
+                                anchors.contains(&anchor),
+                                grid.accepts_geometry_at(&oriented, &anchor, &ruin),
+                                "{}\nat ({}, {}) on\n{}",
+                                oriented,
+                                x,
+                                y,
+                                grid
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// the fields a placement covers, the same for placements that only differ by a symmetry of the geometry
+fn covered(geometry: &Geometry, anchor: &Coordinate) -> Vec<(i32, i32)> {
+    let fields: Vec<Coordinate> = geometry.iter().map(|&pos| pos + *anchor).collect();
+    sorted(fields.iter())
+}
+
+#[test]
+fn symmetric_orientations_are_placed_once() {
+    let grid: Grid = BOARD.parse().unwrap();
+    let ruin = RuinIndicator::default();
+    for geometry in card_geometries() {
+        let placements: Vec<Vec<(i32, i32)>> = grid
+            .legal_placements(&geometry, &ruin)
+            .iter()
+            .map(|placement| covered(&placement.orientation.apply(&geometry), &placement.anchor))
+            .collect();
+        let distinct: Vec<Vec<(i32, i32)>> = placements.iter().cloned().sorted().dedup().collect();
+        assert_eq!(placements.len(), distinct.len(), "{}", geometry);
+
+        // nothing is lost, every orientation still covers the same fields as one of the placements
+        let everything: Vec<Vec<(i32, i32)>> = Orientation::ALL
+            .iter()
+            .flat_map(|orientation| {
+                let oriented = orientation.apply(&geometry);
+                grid.legal_anchors(&oriented, &ruin)
+                    .iter()
+                    .map(|anchor| covered(&oriented, &anchor))
+                    .collect::<Vec<_>>()
+            })
+            .sorted()
+            .dedup()
+            .collect();
+        assert_eq!(distinct, everything, "{}", geometry);
+    }
+
+    let free = grid.all().filter(|field| field.is_free()).count();
+    assert_eq!(
+        grid.legal_placements(&Geometry::default(), &ruin).len(),
+        free
+    );
+}
+
+// the board mirrored and rotated as a whole
+fn transformed(grid: &Grid, orientation: &Orientation) -> Grid {
+    let mut rows = vec![vec!['.'; Grid::SIZE]; Grid::SIZE];
+    for field in grid.all() {
+        let pos = transform(orientation, &field.position());
+        rows[Grid::SIZE - 1 - pos.y as usize][pos.x as usize] = field.symbol();
+    }
+    rows.iter()
+        .map(|row| row.iter().collect::<String>())
+        .join("\n")
+        .parse()
+        .unwrap()
+}
+
+#[test]
+fn fingerprints_are_the_same_for_every_symmetry_of_the_map() {
+    let mut grid: Grid = SYMMETRIC_BOARD.parse().unwrap();
+    assert_eq!(grid.fingerprint().symmetries().len(), 8);
+    grid.try_cultivate(&shape("@#\n#.", Cultivation::Forest), &(2, 3).into())
+        .unwrap();
+    grid.try_cultivate(&shape("@##", Cultivation::Village), &(5, 7).into())
+        .unwrap();
+    let canonical = grid.fingerprint().canonical();
+    for orientation in Orientation::ALL {
+        let other = transformed(&grid, &orientation);
+        assert_eq!(other.fingerprint().canonical(), canonical, "{}", other);
+        assert!(other.is_equivalent(&grid), "{}", other);
+    }
+
+    let before = transformed(&grid, &Orientation::default());
+    grid.try_cultivate(&shape("@", Cultivation::Farm), &(8, 1).into())
+        .unwrap();
+    assert_ne!(grid.fingerprint().canonical(), canonical);
+    assert!(!grid.is_equivalent(&before));
+}
+
+#[test]
+fn analytics_find_squares_diagonals_and_enclosed_fields() {
+    let grid: Grid = "
+        ...........
+        ...........
+        ..fff......
+        ..f.f......
+        ..fff......
+        ...........
+        ...........
+        ......www..
+        ......www..
+        ......www..
+        ...........
+    "
+    .parse()
+    .unwrap();
+
+    // the ring of forest has a hole, only the lake is a filled square
+    assert_eq!(
+        grid.largest_square(Occupancy::Filled),
+        Rect {
+            origin: (6, 1).into(),
+            width: 3,
+            height: 3,
+        }
+    );
+
+    for direction in [Diagonal::Falling, Diagonal::Rising] {
+        assert_eq!(grid.diagonals(direction).count(), 2 * Grid::SIZE - 1);
+        assert_eq!(
+            grid.diagonals(direction).flatten().count(),
+            Grid::SIZE * Grid::SIZE
+        );
+    }
+    let corner: Vec<Coordinate> = grid
+        .diagonal(Diagonal::Falling, 0)
+        .map(|field| field.position())
+        .collect();
+    assert_eq!(corner, vec![(0, 0).into()]);
+    // both run through the lake, the falling one crosses the forest as well
+    for (direction, nth, expected) in [(Diagonal::Falling, 9, 5), (Diagonal::Rising, 15, 3)] {
+        let cultivated = grid
+            .diagonal(direction, nth)
+            .filter(|field| !field.is_free())
+            .count();
+        assert_eq!(cultivated, expected);
+    }
+
+    let enclosed: Vec<_> = grid.enclosed_regions(10).collect();
+    assert_eq!(enclosed.len(), 1);
+    assert_eq!(sorted(enclosed[0].fields.iter()), vec![(3, 7)]);
+    assert!(!enclosed[0].touches_edge);
+}
+
+#[test]
+fn goblins_are_not_recultivated() {
+    let mut grid: Grid = BOARD.parse().unwrap();
+    let farm = Shape::bonus(&Geometry::default(), &Cultivation::Farm, true);
+    assert!(grid.try_recultivate(&farm, &(8, 2).into()).is_err());
+    assert!(grid.try_recultivate(&farm, &(0, 0).into()).is_err());
+    assert!(grid.try_recultivate(&farm, &(1, 4).into()).is_ok());
+    assert_eq!(grid.at(&(1, 4).into()).unwrap().symbol(), 'a');
+}
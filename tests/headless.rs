@@ -0,0 +1,322 @@
+use std::{env, fs};
+
+use karthograph::{
+    card::RuinIndicator,
+    config::GameConfig,
+    grid::{Coordinate, Grid},
+    headless::{HeadlessGame, Step},
+    seasons::SeasonType,
+    shape::Orientation,
+    GameState,
+};
+
+fn game(seed: u64) -> HeadlessGame {
+    let mut game = HeadlessGame::new(GameConfig {
+        seed: Some(seed),
+        ..Default::default()
+    });
+    game.load().unwrap();
+    game
+}
+
+fn cultivated_fields(grid: &Grid) -> usize {
+    grid.all()
+        .filter(|field| field.cultivation.is_some())
+        .count()
+}
+
+/// the first field the shape in hand fits on
+fn free_spot(game: &mut HeadlessGame) -> Option<Coordinate> {
+    let shape = game.shape()?;
+    let grid = game.grid();
+    (0..Grid::SIZE)
+        .flat_map(|x| (0..Grid::SIZE).map(move |y| Coordinate::from((x, y))))
+        .find(|coord| shape.fits(grid, coord))
+}
+
+/// takes the first option of the card, that fits somewhere
+fn choose_placeable(game: &mut HeadlessGame) -> Coordinate {
+    for option in 0.. {
+        game.play(vec![Step::Choose(option)])
+            .expect("one of the options fits");
+        if let Some(spot) = free_spot(game) {
+            return spot;
+        }
+    }
+    unreachable!()
+}
+
+#[test]
+fn loads_and_reveals_a_card() {
+    let mut game = game(1);
+    assert_eq!(game.state(), GameState::SeasonState);
+    assert!(game.card().is_some());
+    assert!(game.shape().is_none());
+    assert_eq!(cultivated_fields(game.grid()), 0);
+}
+
+#[test]
+fn placing_a_shape_cultivates_its_fields() {
+    let mut game = game(2);
+    let spot = choose_placeable(&mut game);
+    let shape = game.shape().unwrap();
+
+    game.play(vec![Step::Move(spot), Step::Place]).unwrap();
+
+    assert!(game.shape().is_none());
+    assert_eq!(cultivated_fields(game.grid()), shape.geometry().len());
+    assert!(shape
+        .geometry()
+        .iter()
+        .all(|&pos| game.grid().is_cultivated(&(pos + spot))));
+    // the next card is revealed right away
+    assert!(game.card().is_some());
+}
+
+#[test]
+fn placing_outside_of_the_rules_changes_nothing() {
+    let mut game = game(3);
+    game.play(vec![Step::Choose(0)]).unwrap();
+    let shape = game.shape().unwrap();
+    let blocked = (0..Grid::SIZE)
+        .flat_map(|x| (0..Grid::SIZE).map(move |y| Coordinate::from((x, y))))
+        .find(|coord| !shape.fits(game.grid(), coord))
+        .unwrap();
+
+    game.play(vec![Step::Move(blocked), Step::Place]).unwrap();
+
+    assert!(game.shape().is_some());
+    assert_eq!(cultivated_fields(game.grid()), 0);
+}
+
+#[test]
+fn rotating_and_mirroring_turn_the_shape_in_hand() {
+    let mut game = game(4);
+    game.play(vec![Step::Choose(0)]).unwrap();
+    let original = game.shape().unwrap().geometry().clone();
+
+    game.play(vec![Step::RotateClockwise]).unwrap();
+    assert_eq!(
+        *game.shape().unwrap().geometry(),
+        Orientation::new(false, 1).apply(&original)
+    );
+
+    game.play(vec![Step::RotateCounterClockwise, Step::Mirror])
+        .unwrap();
+    assert_eq!(
+        *game.shape().unwrap().geometry(),
+        Orientation::new(true, 0).apply(&original)
+    );
+}
+
+#[test]
+fn a_seed_reproduces_the_game() {
+    let mut first = game(5);
+    let mut second = game(5);
+
+    for season in [SeasonType::Spring, SeasonType::Summer] {
+        let names = |game: &HeadlessGame| {
+            let (a, b) = game.objectives().objectives_for_season(&season);
+            (a.name(), b.name())
+        };
+        assert_eq!(names(&first), names(&second));
+    }
+
+    first.play(vec![Step::Choose(0)]).unwrap();
+    second.play(vec![Step::Choose(0)]).unwrap();
+    let (first, second) = (first.shape().unwrap(), second.shape().unwrap());
+    assert_eq!(first.geometry(), second.geometry());
+    assert_eq!(first.cultivation(), second.cultivation());
+}
+
+#[test]
+fn a_whole_game_can_be_played() {
+    let svg = env::temp_dir().join("karthograph_whole_game.svg");
+    let summary = env::temp_dir().join("karthograph_whole_game.json");
+    let mut game = HeadlessGame::new(GameConfig {
+        seed: Some(6),
+        svg: Some(svg.to_string_lossy().into_owned()),
+        summary: Some(summary.to_string_lossy().into_owned()),
+        ..Default::default()
+    });
+    game.load().unwrap();
+
+    for _ in 0..100 {
+        match game.state() {
+            GameState::End => break,
+            GameState::SeasonScoreState => game.play(vec![Step::Continue]).unwrap(),
+            _ => {
+                let spot = choose_placeable(&mut game);
+                game.play(vec![Step::Move(spot), Step::Place]).unwrap();
+            }
+        }
+    }
+
+    assert_eq!(game.state(), GameState::End);
+    let seasons = [
+        SeasonType::Spring,
+        SeasonType::Summer,
+        SeasonType::Autumn,
+        SeasonType::Winter,
+    ];
+    assert!(seasons
+        .iter()
+        .all(|season| game.objectives().season_score(season).is_some()));
+    assert!(cultivated_fields(game.grid()) > 0);
+
+    // the final map is exported, with every objective in its legend
+    let exported = fs::read_to_string(&svg).unwrap();
+    assert_eq!(exported, game.svg());
+    assert!(game
+        .objectives()
+        .objectives()
+        .all(|objective| exported.contains(objective.name())));
+    assert!(exported.contains(&format!(
+        "Total: {} points",
+        game.objectives().total_score()
+    )));
+
+    // so is the summary, with every turn and season
+    let exported: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&summary).unwrap()).unwrap();
+    let summary = game.summary();
+    assert_eq!(exported, serde_json::to_value(&summary).unwrap());
+    assert_eq!(exported["seed"], 6);
+    assert_eq!(summary.seasons.len(), 4);
+    assert_eq!(
+        summary
+            .seasons
+            .iter()
+            .map(|season| season.total)
+            .sum::<isize>(),
+        summary.total
+    );
+    assert!(summary.turns.iter().all(|turn| !turn.placed.is_empty()));
+    let placed: usize = summary
+        .turns
+        .iter()
+        .flat_map(|turn| turn.placed.iter())
+        .map(|placed| placed.fields.len())
+        .sum();
+    assert_eq!(placed, cultivated_fields(game.grid()));
+}
+
+#[test]
+fn a_board_code_continues_the_game() {
+    let mut original = game(7);
+    for _ in 0..3 {
+        let spot = choose_placeable(&mut original);
+        original.play(vec![Step::Move(spot), Step::Place]).unwrap();
+    }
+    let code = original.board_code();
+
+    let mut loaded = HeadlessGame::new(GameConfig {
+        position: Some(code.clone()),
+        ..Default::default()
+    });
+    loaded.load().unwrap();
+
+    assert_eq!(loaded.grid().to_string(), original.grid().to_string());
+    assert_eq!(
+        loaded.card().unwrap().to_string(),
+        original.card().unwrap().to_string()
+    );
+    assert_eq!(
+        loaded.season().passed_time(),
+        original.season().passed_time()
+    );
+    assert_eq!(loaded.board_code(), code);
+}
+
+#[test]
+fn an_invalid_board_code_fails_to_load() {
+    let mut game = HeadlessGame::new(GameConfig {
+        position: Some("not a board code".to_string()),
+        ..Default::default()
+    });
+    assert!(game.load().is_err());
+}
+
+#[test]
+fn no_ruin_is_pending_at_the_end_of_a_season() {
+    let mut game = game(8);
+    while game.state() == GameState::SeasonState {
+        let spot = choose_placeable(&mut game);
+        game.play(vec![Step::Move(spot), Step::Place]).unwrap();
+    }
+    assert_eq!(game.state(), GameState::SeasonScoreState);
+    assert!(!**game.app.world.get_resource::<RuinIndicator>().unwrap());
+}
+
+#[test]
+fn every_player_places_the_same_card_on_their_own_map() {
+    let mut game = HeadlessGame::new(GameConfig {
+        seed: Some(2),
+        players: 2,
+        ..Default::default()
+    });
+    game.load().unwrap();
+    let card = game.card().map(|card| card.to_string());
+    let passed_time = game.season().passed_time();
+
+    let spot = choose_placeable(&mut game);
+    game.play(vec![Step::Move(spot), Step::Place]).unwrap();
+    let placed = cultivated_fields(&game.players().waiting().next().unwrap().grid);
+
+    // the second player gets the card on the empty map, the time passes once
+    assert_eq!(game.players().current(), 1);
+    assert_eq!(game.card().map(|card| card.to_string()), card);
+    assert_eq!(cultivated_fields(game.grid()), 0);
+    assert_eq!(game.season().passed_time(), passed_time);
+
+    let spot = choose_placeable(&mut game);
+    game.play(vec![Step::Move(spot), Step::Place]).unwrap();
+
+    // then the first player draws the next card
+    assert_eq!(game.players().current(), 0);
+    assert_eq!(cultivated_fields(game.grid()), placed);
+    assert!(game.season().passed_time() > passed_time);
+    let players: Vec<usize> = game
+        .summary()
+        .turns
+        .iter()
+        .map(|turn| turn.player)
+        .collect();
+    assert_eq!(players, vec![0, 1, 0]);
+}
+
+#[test]
+fn every_player_is_scored_at_the_end() {
+    let mut game = HeadlessGame::new(GameConfig {
+        seed: Some(6),
+        players: 3,
+        ..Default::default()
+    });
+    game.load().unwrap();
+
+    for _ in 0..300 {
+        match game.state() {
+            GameState::End => break,
+            GameState::SeasonScoreState => game.play(vec![Step::Continue]).unwrap(),
+            _ => {
+                let spot = choose_placeable(&mut game);
+                game.play(vec![Step::Move(spot), Step::Place]).unwrap();
+            }
+        }
+    }
+
+    assert_eq!(game.state(), GameState::End);
+    let summary = game.summary();
+    assert_eq!(summary.seasons.len(), 4);
+    assert_eq!(summary.others.len(), 2);
+    assert!(summary.others.iter().all(|other| other.seasons.len() == 4));
+    let turns = |player| {
+        summary
+            .turns
+            .iter()
+            .filter(|turn| turn.player == player)
+            .count()
+    };
+    assert_eq!(turns(1), turns(0));
+    assert_eq!(turns(2), turns(0));
+}
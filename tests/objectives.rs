@@ -0,0 +1,189 @@
+use std::{fs, path::Path};
+
+use karthograph::{
+    card::RuinIndicator,
+    card_pile::CardPile,
+    fixture::Fixture,
+    grid::{Cultivation, Grid},
+    objective::{all_objectives, GameObjectives},
+    seasons::SeasonType,
+    shape::{Geometry, Shape},
+};
+use rand::{prelude::SliceRandom, rngs::StdRng, Rng, SeedableRng};
+
+/// every `.board` file in `tests/fixtures`, by file name
+fn fixtures() -> Vec<(String, Fixture)> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let mut result: Vec<(String, Fixture)> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().map_or(false, |ext| ext == "board"))
+        .map(|path| {
+            let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+            let fixture: Fixture = fs::read_to_string(&path)
+                .unwrap()
+                .parse()
+                .unwrap_or_else(|err| panic!("{}: {}", name, err));
+            (name, fixture)
+        })
+        .collect();
+    result.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
+    result
+}
+
+#[test]
+fn objectives_score_the_fixtures_as_expected() {
+    let failures: Vec<String> = fixtures()
+        .iter()
+        .flat_map(|(name, fixture)| {
+            fixture
+                .mismatches()
+                .unwrap_or_else(|err| panic!("{}: {}", name, err))
+                .into_iter()
+                .map(move |mismatch| {
+                    format!(
+                        "{}: {} scored {} instead of {} on\n{}",
+                        name, mismatch.objective, mismatch.actual, mismatch.expected, fixture.grid
+                    )
+                })
+        })
+        .collect();
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+#[test]
+fn every_objective_has_a_fixture() {
+    let fixtures = fixtures();
+    let missing: Vec<&str> = all_objectives()
+        .iter()
+        .map(|objective| objective.name())
+        .filter(|name| {
+            !fixtures
+                .iter()
+                .any(|(_, fixture)| fixture.expected.iter().any(|(other, _)| other == name))
+        })
+        .collect();
+    assert!(missing.is_empty(), "no fixture for {:?}", missing);
+}
+
+#[test]
+fn upper_bounds_are_never_below_the_score() {
+    for (name, fixture) in fixtures() {
+        for objective in all_objectives() {
+            let score = objective.score(&fixture.grid).points();
+            let bound = objective.upper_bound(&fixture.grid, 0).points();
+            assert!(
+                bound >= score,
+                "{}: {} scored {} above its bound {}",
+                name,
+                objective.name(),
+                score,
+                bound
+            );
+        }
+    }
+}
+
+#[test]
+fn upper_bounds_hold_for_the_fields_cultivated_afterwards() {
+    let cultivations = [
+        Cultivation::Village,
+        Cultivation::Water,
+        Cultivation::Farm,
+        Cultivation::Forest,
+        Cultivation::Goblin,
+    ];
+    let mut rng = StdRng::seed_from_u64(37);
+    for (name, fixture) in fixtures() {
+        for fields_left in [1, 4, 12] {
+            let mut grid: Grid = fixture.grid.to_string().parse().unwrap();
+            let mut free: Vec<_> = grid
+                .all()
+                .filter(|field| field.is_free())
+                .map(|field| field.position())
+                .collect();
+            free.shuffle(&mut rng);
+            for pos in free.iter().take(fields_left) {
+                let cultivation = cultivations.choose(&mut rng).unwrap();
+                let field = Shape::new(&Geometry::default(), cultivation, &false.into(), false);
+                grid.try_cultivate(&field, pos).unwrap();
+            }
+            for objective in all_objectives() {
+                let score = objective.score(&grid).points();
+                let bound = objective.upper_bound(&fixture.grid, fields_left).points();
+                assert!(
+                    bound >= score,
+                    "{}: {} scored {} above its bound {} with {} more fields on\n{}",
+                    name,
+                    objective.name(),
+                    score,
+                    bound,
+                    fields_left,
+                    grid
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn tracked_scores_follow_every_placement() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/content.cardpile");
+    let pile: CardPile = fs::read_to_string(path).unwrap().parse().unwrap();
+    let shapes: Vec<Shape> = pile
+        .cards
+        .iter()
+        .flat_map(|card| card.options(&RuinIndicator::default()))
+        .collect();
+    let seasons = [
+        SeasonType::Spring,
+        SeasonType::Summer,
+        SeasonType::Autumn,
+        SeasonType::Winter,
+    ];
+
+    // the objectives are drawn, enough games show each of them
+    for seed in 0..24 {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut objectives = GameObjectives::new(&mut rng);
+        let mut grid = Grid::new();
+        for turn in 0..40 {
+            let shape = shapes.choose(&mut rng).unwrap();
+            let placements = grid.legal_placements(shape.geometry(), &shape.ruin());
+            let placement = match placements.choose(&mut rng) {
+                Some(placement) => placement,
+                None => break,
+            };
+            let placed = Shape::new(
+                &placement.orientation.apply(shape.geometry()),
+                &shape.cultivation(),
+                &shape.ruin(),
+                false,
+            );
+            let mut changed = grid.try_cultivate(&placed, &placement.anchor).unwrap();
+            // now and then a field is recultivated, like the skill does it, goblins stay
+            if rng.gen_bool(0.2) && shape.cultivation() != Cultivation::Goblin {
+                let field = Shape::bonus(&Geometry::default(), &Cultivation::Goblin, true);
+                let pos = changed[0];
+                changed.extend(grid.try_recultivate(&field, &pos).unwrap());
+            }
+            objectives.update_scores(&grid, &changed);
+
+            for season in seasons.iter() {
+                let (a, b) = objectives.objectives_for_season(season);
+                let (tracked_a, tracked_b) = objectives.current_scores(season);
+                for (objective, tracked) in [(a, tracked_a), (b, tracked_b)] {
+                    assert_eq!(
+                        tracked.map(|score| score.points()),
+                        Some(objective.score(&grid).points()),
+                        "seed {}, turn {}: {} on\n{}",
+                        seed,
+                        turn,
+                        objective.name(),
+                        grid
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use karthograph::{
+    card::RuinIndicator,
+    grid::{Coordinate, Cultivation, FieldChange, Grid},
+    objective::{CoinSource, GameObjectives},
+    players::{Players, Seat},
+    shape::{Geometry, Shape},
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+fn objectives() -> GameObjectives {
+    GameObjectives::new(&mut StdRng::seed_from_u64(3))
+}
+
+fn forest() -> Shape {
+    let geometry: Geometry = "@".parse().unwrap();
+    Shape::new(
+        &geometry,
+        &Cultivation::Forest,
+        &RuinIndicator::default(),
+        false,
+    )
+}
+
+fn names(objectives: &GameObjectives) -> Vec<&'static str> {
+    objectives
+        .objectives()
+        .map(|objective| objective.name())
+        .collect()
+}
+
+#[test]
+fn objectives_are_shared_without_coins() {
+    let mut objectives = objectives();
+    objectives.add_coin(vec![(0, 0).into()], CoinSource::Shape);
+    let shared = objectives.share();
+
+    assert_eq!(names(&shared), names(&objectives));
+    assert_eq!(shared.coins(), 0);
+}
+
+#[test]
+fn passing_cycles_through_the_maps() {
+    let mut grid = Grid::new();
+    let mut objectives = objectives();
+    let mut other = Grid::new();
+    other.try_cultivate(&forest(), &(0, 0).into()).unwrap();
+    let mut players = Players::new(vec![Seat {
+        grid: other,
+        objectives: objectives.share(),
+    }]);
+    objectives.add_coin(vec![(10, 10).into()], CoinSource::Shape);
+    assert_eq!(players.count(), 2);
+
+    // the second player places the same card
+    assert!(players.pass(&mut grid, &mut objectives));
+    assert_eq!(players.current(), 1);
+    assert!(grid.is_cultivated(&(0, 0).into()));
+    assert_eq!(objectives.coins(), 0);
+
+    // then the first one draws the next card
+    assert!(!players.pass(&mut grid, &mut objectives));
+    assert_eq!(players.current(), 0);
+    assert!(!grid.is_cultivated(&(0, 0).into()));
+    assert_eq!(objectives.coins(), 1);
+}
+
+#[test]
+fn a_single_player_keeps_the_map() {
+    let mut grid = Grid::new();
+    grid.try_cultivate(&forest(), &(0, 0).into()).unwrap();
+    let mut objectives = objectives();
+    let mut players = Players::default();
+
+    assert!(!players.pass(&mut grid, &mut objectives));
+    assert_eq!(players.current(), 0);
+    assert!(grid.is_cultivated(&(0, 0).into()));
+}
+
+#[test]
+fn the_next_map_is_rendered_from_the_changes() {
+    let mut grid = Grid::new();
+    grid.track_changes();
+    grid.drain_changes().count();
+    let mut next = Grid::new();
+    next.try_cultivate(&forest(), &(0, 0).into()).unwrap();
+
+    grid.hand_over_changes(&mut next);
+
+    let changes: Vec<(Coordinate, FieldChange)> = next
+        .drain_changes()
+        .map(|changed| (changed.position, changed.change))
+        .collect();
+    let cultivated = FieldChange::Cultivation {
+        old: None,
+        new: Some(Cultivation::Forest),
+    };
+    assert_eq!(changes, vec![((0, 0).into(), cultivated)]);
+    // the old map is not shown anymore
+    grid.try_cultivate(&forest(), &(10, 10).into()).unwrap();
+    assert!(!grid.has_changes());
+}
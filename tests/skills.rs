@@ -0,0 +1,154 @@
+use karthograph::{
+    card::RuinIndicator,
+    grid::{Cultivation, Grid},
+    objective::{CoinSource, GameObjectives},
+    seasons::SeasonType,
+    shape::{Geometry, Shape},
+    skill::{SkillEffect, Skills},
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+/// the skills of the first game, that reveals the skill, and its index
+fn skills_with(effect: SkillEffect) -> (Skills, usize) {
+    (0..)
+        .map(|seed| Skills::new(&mut StdRng::seed_from_u64(seed)))
+        .find_map(|skills| {
+            let index = skills
+                .cards()
+                .iter()
+                .position(|card| card.effect() == effect)?;
+            Some((skills, index))
+        })
+        .unwrap()
+}
+
+fn objectives_with_coins(coins: i32) -> GameObjectives {
+    let mut objectives = GameObjectives::new(&mut StdRng::seed_from_u64(0));
+    for x in 0..coins {
+        objectives.add_coin(vec![(x, 0).into()], CoinSource::Mountain);
+    }
+    objectives
+}
+
+fn forest(geometry: &str) -> Shape {
+    let geometry: Geometry = geometry.parse().unwrap();
+    Shape::new(
+        &geometry,
+        &Cultivation::Forest,
+        &RuinIndicator::default(),
+        false,
+    )
+}
+
+/// the bonus shape of the skill after placing a forest on the grid
+fn bonus_after_forest(effect: SkillEffect) -> (Shape, Shape) {
+    let (mut skills, index) = skills_with(effect);
+    let mut objectives = objectives_with_coins(3);
+    let mut grid = Grid::new();
+    let placed = forest("@#");
+    grid.try_cultivate(&placed, &(0, 0).into()).unwrap();
+
+    skills.buy(index, &false.into(), &mut objectives).unwrap();
+    let bonus = skills
+        .bonus_shape(&placed, &grid, &mut objectives)
+        .ok()
+        .flatten()
+        .expect("the grid takes the bonus shape");
+    (placed, bonus)
+}
+
+#[test]
+fn ruins_are_only_ignored_for_cards_that_need_one() {
+    // not every game reveals the skill
+    let (mut skills, pathfinder) = skills_with(SkillEffect::IgnoreRuin);
+    let mut objectives = objectives_with_coins(1);
+
+    assert!(skills
+        .buy(pathfinder, &false.into(), &mut objectives)
+        .is_err());
+    assert_eq!(skills.active(), None);
+    assert_eq!(objectives.coins(), 1);
+
+    assert_eq!(
+        skills.buy(pathfinder, &true.into(), &mut objectives),
+        Ok(SkillEffect::IgnoreRuin)
+    );
+    assert_eq!(objectives.coins(), 0);
+}
+
+#[test]
+fn extra_splinters_are_a_single_field_of_the_placed_cultivation() {
+    let (_, bonus) = bonus_after_forest(SkillEffect::ExtraSplinter);
+
+    assert_eq!(bonus.geometry(), &Geometry::default());
+    assert_eq!(bonus.cultivation(), Cultivation::Forest);
+    assert!(bonus.is_bonus());
+    assert!(!bonus.recultivates());
+}
+
+#[test]
+fn extra_copies_repeat_the_placed_shape() {
+    let (placed, bonus) = bonus_after_forest(SkillEffect::ExtraCopy);
+
+    assert_eq!(bonus.geometry(), placed.geometry());
+    assert_eq!(bonus.cultivation(), Cultivation::Forest);
+    assert!(bonus.is_bonus());
+    assert!(!bonus.recultivates());
+}
+
+#[test]
+fn recultivation_overwrites_a_single_field() {
+    let (_, bonus) = bonus_after_forest(SkillEffect::Recultivate);
+
+    assert_eq!(bonus.geometry(), &Geometry::default());
+    assert_eq!(bonus.cultivation(), Cultivation::Forest);
+    assert!(bonus.is_bonus());
+    assert!(bonus.recultivates());
+}
+
+#[test]
+fn skills_without_room_for_their_bonus_are_refunded() {
+    let (mut skills, index) = skills_with(SkillEffect::Recultivate);
+    let mut objectives = objectives_with_coins(3);
+    let cost = skills.cards()[index].cost();
+    skills.buy(index, &false.into(), &mut objectives).unwrap();
+    assert_eq!(objectives.coins(), 3 - cost);
+
+    // nothing is cultivated, so there is nothing to recultivate
+    let refund = skills
+        .bonus_shape(&forest("@"), &Grid::new(), &mut objectives)
+        .err()
+        .expect("the skill is refunded");
+
+    assert_eq!((refund.index, refund.cost), (index, cost));
+    assert_eq!(objectives.coins(), 3);
+}
+
+#[test]
+fn spent_coins_are_not_scored() {
+    let (mut skills, index) = skills_with(SkillEffect::ExtraSplinter);
+    let mut objectives = objectives_with_coins(2);
+    skills.buy(index, &false.into(), &mut objectives).unwrap();
+
+    let score = objectives.score_season(&SeasonType::Spring, &Grid::new());
+
+    assert_eq!(score.coin_count, 2 - skills.cards()[index].cost());
+}
+
+#[test]
+fn only_one_skill_is_bought_per_card() {
+    let (mut skills, index) = skills_with(SkillEffect::ExtraSplinter);
+    let other = (index + 1) % skills.cards().len();
+    let mut objectives = objectives_with_coins(6);
+    let cost = skills.cards()[index].cost();
+    skills.buy(index, &true.into(), &mut objectives).unwrap();
+
+    assert!(skills.buy(other, &true.into(), &mut objectives).is_err());
+    assert!(skills.buy(index, &true.into(), &mut objectives).is_err());
+    assert_eq!(skills.active(), Some(index));
+    assert_eq!(objectives.coins(), 6 - cost);
+
+    // the next card can have a skill again
+    skills.end_turn();
+    assert!(skills.buy(other, &true.into(), &mut objectives).is_ok());
+}
@@ -0,0 +1,88 @@
+use itertools::Itertools;
+use karthograph::{board_code::BoardCode, card::Card, grid::Grid, shape::Geometry};
+
+const BOARD: &str = "
+    ...........
+    ...M.....R.
+    .R.vv......
+    ...vV...M..
+    .......ww..
+    .....m.wW..
+    .ff........
+    .ffF...aa..
+    ........gg.
+    ..M....R...
+    ...........
+";
+
+#[test]
+fn a_board_is_written_as_it_is_read() {
+    let grid: Grid = BOARD.parse().unwrap();
+    let written = grid.to_string();
+    assert_eq!(
+        written.lines().collect::<Vec<_>>(),
+        BOARD.split_whitespace().collect::<Vec<_>>()
+    );
+    assert_eq!(written.parse::<Grid>().unwrap().to_string(), written);
+}
+
+#[test]
+fn a_geometry_keeps_its_origin() {
+    // fields are read row by row, so their order can change
+    let sorted = |geometry: &Geometry| geometry.iter().copied().sorted().collect::<Vec<_>>();
+    let geometry: Geometry = ".#\n@#\n.#".parse().unwrap();
+    for (_, oriented) in geometry.orientations() {
+        let written = oriented.to_string();
+        let read: Geometry = written.parse().unwrap();
+        assert_eq!(sorted(&read), sorted(&oriented), "\n{}", written);
+    }
+
+    // the origin does not have to be part of the geometry
+    let detached: Geometry = "#.\n.+".parse().unwrap();
+    assert_eq!(detached.to_string(), "#.\n.+");
+    // without an origin, the lowest field is at zero
+    let normalized: Geometry = "##\n#.".parse().unwrap();
+    assert_eq!(normalized.to_string(), "##\n@.");
+}
+
+#[test]
+fn cards_are_written_as_they_are_read() {
+    for text in [
+        "splinter",
+        "ruin",
+        "shape f\n@.\n.#\n\n.#\n@#\n#.",
+        "cultivation a w\n#..\n@##",
+    ] {
+        let card: Card = text.parse().unwrap();
+        assert_eq!(card.to_string(), text);
+    }
+    assert!("shape f\n@#".parse::<Card>().is_err());
+    assert!("shape x\n@\n\n@".parse::<Card>().is_err());
+}
+
+#[test]
+fn a_board_code_restores_the_position() {
+    let grid: Grid = BOARD.parse().unwrap();
+    let code = BoardCode::encode(&grid, Some(u64::MAX - 3), Some(17));
+    let read: BoardCode = code.parse().unwrap();
+    assert_eq!(read.grid.to_string(), grid.to_string());
+    assert_eq!(read.seed, Some(u64::MAX - 3));
+    assert_eq!(read.turn, Some(17));
+
+    // typed by hand
+    let typed: String = code
+        .to_lowercase()
+        .chars()
+        .collect::<Vec<_>>()
+        .chunks(8)
+        .map(|chunk| chunk.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join(" ");
+    assert_eq!(typed.parse::<BoardCode>().unwrap().to_string(), code);
+
+    let bare = BoardCode::encode(&grid, None, None);
+    assert!(bare.len() < code.len());
+    assert!(bare.parse::<BoardCode>().unwrap().seed.is_none());
+    assert!(code[..code.len() - 4].parse::<BoardCode>().is_err());
+    assert!("not a code!".parse::<BoardCode>().is_err());
+}